@@ -4,11 +4,11 @@ include!("data/plain_512.rs");
 include!("data/plain_1024.rs");
 
 use criterion::*;
-use radixmap::RadixMap;
+use radixmap::{RadixMap, PlainRadixMap};
 
 macro_rules! lookup {
-    ($test:expr, $size:literal, $urls:expr, $path:expr) => {{
-        let mut map = RadixMap::new();
+    ($test:expr, $kind:ident, $name:literal, $size:literal, $urls:expr, $path:expr) => {{
+        let mut map = $kind::new();
 
         for url in $urls {
             let _ = black_box(map.insert(*url, true));
@@ -16,17 +16,22 @@ macro_rules! lookup {
 
         assert_eq!(map.len(), $size);
 
-        $test.bench_function(concat!("lookup-plain-", stringify!($size)), |b| b.iter(|| {
+        $test.bench_function(concat!($name, stringify!($size)), |b| b.iter(|| {
             black_box(map.get(&$path));
         }));
     }};
 }
 
 fn benchmark(c: &mut Criterion) {
-    lookup!(c, 16, PLAIN_URLS_16, PLAIN_PATH_16);
-    lookup!(c, 64, PLAIN_URLS_64, PLAIN_PATH_64);
-    lookup!(c, 512, PLAIN_URLS_512, PLAIN_PATH_512);
-    lookup!(c, 1024, PLAIN_URLS_1024, PLAIN_PATH_1024);
+    lookup!(c, RadixMap, "lookup-plain-", 16, PLAIN_URLS_16, PLAIN_PATH_16);
+    lookup!(c, RadixMap, "lookup-plain-", 64, PLAIN_URLS_64, PLAIN_PATH_64);
+    lookup!(c, RadixMap, "lookup-plain-", 512, PLAIN_URLS_512, PLAIN_PATH_512);
+    lookup!(c, RadixMap, "lookup-plain-", 1024, PLAIN_URLS_1024, PLAIN_PATH_1024);
+
+    lookup!(c, PlainRadixMap, "lookup-plain-only-", 16, PLAIN_URLS_16, PLAIN_PATH_16);
+    lookup!(c, PlainRadixMap, "lookup-plain-only-", 64, PLAIN_URLS_64, PLAIN_PATH_64);
+    lookup!(c, PlainRadixMap, "lookup-plain-only-", 512, PLAIN_URLS_512, PLAIN_PATH_512);
+    lookup!(c, PlainRadixMap, "lookup-plain-only-", 1024, PLAIN_URLS_1024, PLAIN_PATH_1024);
 }
 
 criterion_group!(
@@ -1,21 +1,81 @@
 //! Common defines
-pub(crate) use std::hash::Hash;
-pub(crate) use std::hash::Hasher;
-pub(crate) use std::fmt::Debug;
-pub(crate) use std::ops::Index;
-pub(crate) use std::ops::IndexMut;
-pub(crate) use std::fmt::Formatter;
-pub(crate) use std::iter::Peekable;
-pub(crate) use std::str::Utf8Error;
-pub(crate) use std::collections::VecDeque;
+pub(crate) use core::hash::Hash;
+pub(crate) use core::cmp::Ordering;
+pub(crate) use core::hash::Hasher;
+pub(crate) use core::fmt::Debug;
+pub(crate) use core::ops::Index;
+pub(crate) use core::ops::IndexMut;
+pub(crate) use core::fmt::Formatter;
+pub(crate) use core::iter::Peekable;
+pub(crate) use core::str::Utf8Error;
+pub(crate) use core::hash::BuildHasherDefault;
+pub(crate) use core::panic::RefUnwindSafe;
+pub(crate) use alloc::collections::BTreeMap;
+pub(crate) use alloc::collections::VecDeque;
+pub(crate) use alloc::sync::Arc;
+pub(crate) use alloc::borrow::Cow;
+pub(crate) use alloc::boxed::Box;
+pub(crate) use alloc::string::String;
+pub(crate) use alloc::vec::Vec;
+pub(crate) use alloc::{format, vec};
 
 pub(crate) use bytes::Bytes;
+#[cfg(feature = "regex")]
 pub(crate) use regex::Regex;
-pub(crate) use vec_map::VecMap;
 pub(crate) use thiserror::Error;
-pub(crate) use indexmap::IndexMap;
+
+/// Insertion-ordered map hashed by FNV-1a, which needs neither std nor a random seed
+pub(crate) type IndexMap<K, V> = indexmap::IndexMap<K, V, BuildHasherDefault<FnvHasher>>;
+
+/// FNV-1a hasher, the keys are short fragments so a cheap hash is enough
+#[derive(Clone, Copy)]
+pub(crate) struct FnvHasher(u64);
+
+impl Default for FnvHasher {
+    #[inline]
+    fn default() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+}
+
+impl Hasher for FnvHasher {
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ *byte as u64).wrapping_mul(0x100000001b3);
+        }
+    }
+}
 
 /// Error Codes
+///
+/// The malformed pattern is reported with its position, and renders the pattern with the bad
+/// fragment underlined
+///
+/// # Examples
+///
+/// ```
+/// use radixmap::{RadixError, RadixMap};
+///
+/// let mut map = RadixMap::new();
+///
+/// let err = map.insert(r"/api/{id:\d+/rest", 1).unwrap_err();
+/// assert_eq!(err.to_string(), "missing closing sign '}' at byte 5\n    /api/{id:\\d+/rest\n         ^^^^^^^^^^^^");
+///
+/// #[cfg(feature = "regex")]
+/// {
+///     let err = map.insert(r"/api/{id:[0-9}", 1).unwrap_err();
+///     assert!(matches!(err, RadixError::PatternInvalid { offset: 9, span: 4, ref source, .. } if matches!(**source, RadixError::RegexInvalid(_))));
+/// }
+///
+/// let err = map.insert(r"/archive[/:year", 1).unwrap_err();
+/// assert!(matches!(err, RadixError::PatternInvalid { offset: 8, span: 1, .. }));
+/// ```
 #[allow(missing_docs)]
 #[derive(Debug, Error)]
 pub enum RadixError {
@@ -31,16 +91,100 @@ pub enum RadixError {
     #[error("{0}")]
     PathMalformed(&'static str),
 
+    #[error("{}", caret(.pattern, *.offset, *.span, .source))]
+    PatternInvalid { pattern: Bytes, offset: usize, span: usize, source: Box<RadixError> },
+
+    #[error("route '{}' conflicts with '{}'", String::from_utf8_lossy(new), String::from_utf8_lossy(existing))]
+    Conflict { existing: Bytes, new: Bytes },
+
     #[error("rule can't be split")]
     RuleIndivisible,
 
+    #[error("rule can't be merged")]
+    RuleUnmergeable,
+
+    #[error("param not found")]
+    ParamNotFound,
+
+    #[error("param can't be parsed")]
+    ParamInvalid,
+
+    #[error("feature '{0}' is disabled")]
+    FeatureDisabled(&'static str),
+
+    #[error("snapshot is invalid: {0}")]
+    SnapshotInvalid(&'static str),
+
+    #[cfg(feature = "std")]
+    #[error("snapshot io failed: {0}")]
+    SnapshotIo(std::io::ErrorKind),
+
+    #[cfg(feature = "glob")]
     #[error("{0}")]
     GlobInvalid(#[from] glob::PatternError),
 
+    #[cfg(feature = "regex")]
     #[error("{0}")]
     RegexInvalid(#[from] regex::Error),
 }
 
+/// Clone trait, glob's error is rebuilt since it isn't Clone
+impl Clone for RadixError {
+    fn clone(&self) -> Self {
+        match self {
+            RadixError::PathEmpty => RadixError::PathEmpty,
+            RadixError::PathNotFound => RadixError::PathNotFound,
+            RadixError::PathInvalid(err) => RadixError::PathInvalid(*err),
+            RadixError::PathMalformed(msg) => RadixError::PathMalformed(msg),
+            RadixError::PatternInvalid { pattern, offset, span, source } => RadixError::PatternInvalid { pattern: pattern.clone(), offset: *offset, span: *span, source: source.clone() },
+            RadixError::Conflict { existing, new } => RadixError::Conflict { existing: existing.clone(), new: new.clone() },
+            RadixError::RuleIndivisible => RadixError::RuleIndivisible,
+            RadixError::RuleUnmergeable => RadixError::RuleUnmergeable,
+            RadixError::ParamNotFound => RadixError::ParamNotFound,
+            RadixError::ParamInvalid => RadixError::ParamInvalid,
+            RadixError::FeatureDisabled(name) => RadixError::FeatureDisabled(name),
+            RadixError::SnapshotInvalid(reason) => RadixError::SnapshotInvalid(reason),
+            #[cfg(feature = "std")]
+            RadixError::SnapshotIo(kind) => RadixError::SnapshotIo(*kind),
+            #[cfg(feature = "glob")]
+            RadixError::GlobInvalid(err) => RadixError::GlobInvalid(glob::PatternError { pos: err.pos, msg: err.msg }),
+            #[cfg(feature = "regex")]
+            RadixError::RegexInvalid(err) => RadixError::RegexInvalid(err.clone()),
+        }
+    }
+}
+
+/// The io error of the snapshot, only its kind is kept so the error stays cloneable
+#[cfg(feature = "std")]
+impl From<std::io::Error> for RadixError {
+    #[inline]
+    fn from(err: std::io::Error) -> Self {
+        RadixError::SnapshotIo(err.kind())
+    }
+}
+
+impl RadixError {
+    /// Internal use only, attach the pattern and the span of the bad fragment to the error.
+    /// The error positioned in a fragment already is moved to where the fragment starts
+    pub(crate) fn at(self, pattern: &Bytes, offset: usize, span: usize) -> Self {
+        match self {
+            RadixError::PatternInvalid { offset: inner, span, source, .. } => {
+                RadixError::PatternInvalid { pattern: pattern.clone(), offset: offset + inner, span, source }
+            }
+            err => RadixError::PatternInvalid { pattern: pattern.clone(), offset, span, source: Box::new(err) },
+        }
+    }
+}
+
+/// Internal use only, render the pattern and underline the bad fragment with carets
+fn caret(pattern: &Bytes, offset: usize, span: usize, source: &RadixError) -> String {
+    let text = String::from_utf8_lossy(pattern);
+    let head = String::from_utf8_lossy(&pattern[..offset.min(pattern.len())]).chars().count();
+    let body = String::from_utf8_lossy(&pattern[offset.min(pattern.len())..(offset + span).min(pattern.len())]).chars().count();
+
+    format!("{} at byte {}\n    {}\n    {}{}", source, offset, text, " ".repeat(head), "^".repeat(body.max(1)))
+}
+
 /// Custom Result
 pub type RadixResult<T> = Result<T, RadixError>;
 
@@ -0,0 +1,1030 @@
+//! Frozen map lays the whole tree out in one contiguous buffer
+//!
+//! The layout is a fixed header, the node records and a blob of the fragments, paths, aliases
+//! and encoded values, followed by a FNV-1a checksum. Nodes are stored in level-order so the
+//! children of a node are adjacent, the regular ones sorted by their key and the special ones in
+//! the order they are tried. Records are made of little-endian u32 words and read in place, so
+//! the buffer can be opened from any byte slice such as an mmapped file. Opening only checks the
+//! structure and compiles the special rules once more, the values are decoded on first read
+use core::ops::Range;
+use super::defs::*;
+use super::map::RadixMap;
+use super::node::RadixWalk;
+use super::params::Params;
+use super::rule::{RadixConfig, RadixRule};
+use super::snapshot::{self, Cursor, RadixCodec};
+
+/// The cell caching a decoded value, None if the value fails to decode
+#[cfg(feature = "std")]
+type Lazy<V> = std::sync::OnceLock<Option<V>>;
+
+/// The cell caching a decoded value, None if the value fails to decode
+#[cfg(not(feature = "std"))]
+type Lazy<V> = core::cell::OnceCell<Option<V>>;
+
+/// The leading bytes of a frozen map
+const MAGIC: &[u8; 4] = b"RDXF";
+
+/// The format version, bumped on every incompatible change
+const VERSION: u32 = 1;
+
+/// The header length: magic, version, flags, size, node count, blob length and separator length
+const HEADER: usize = 28;
+
+/// The record length, the words are listed in Record
+const RECORD: usize = 14 * 4;
+
+/// The absent key, offset or rule
+const NONE: u32 = u32::MAX;
+
+/// The kinds of the rule
+const PLAIN: u32 = 0;
+const ICASE: u32 = 1;
+const SPECIAL: u32 = 2;
+
+/// The read-only radix map whose nodes, fragments and child indexes live in one contiguous
+/// buffer, built from a RadixMap or opened in place from the bytes it was saved as
+pub struct FrozenRadixMap<'a, V> {
+    /// The whole image, borrowed if it's opened in place
+    data: Cow<'a, [u8]>,
+
+    /// The number of node records
+    count: usize,
+
+    /// The offset of the blob
+    blob: usize,
+
+    /// The number of data nodes
+    size: usize,
+
+    /// The options of the rules
+    config: RadixConfig,
+
+    /// The compiled special rules in level-order
+    rules: Vec<RadixRule>,
+
+    /// The decoded values, one cell per node
+    values: Vec<Lazy<V>>,
+}
+
+impl<'a, V: RadixCodec> FrozenRadixMap<'a, V> {
+    /// Open the frozen map in place, the bytes are never copied. Only the structure is checked,
+    /// which is enough for lookups to read the records safely, call verify to check the checksum
+    /// and the values as well
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{FrozenRadixMap, RadixError, RadixMap, RadixResult};
+    ///
+    /// # #[cfg(feature = "glob")]
+    /// fn main() -> RadixResult<()> {
+    ///     let map = RadixMap::try_from([("/api/v1", 1u32), ("/api/:id", 2), ("/static/*", 3)])?;
+    ///     let data = FrozenRadixMap::try_from(&map)?.as_bytes().to_vec(); // e.g. written to disk and mmapped
+    ///
+    ///     let frozen = FrozenRadixMap::<u32>::open(&data)?;
+    ///
+    ///     assert_eq!(frozen.len(), 3);
+    ///     assert_eq!(frozen.get(b"/api/v1"), Some(&1));
+    ///     assert_eq!(frozen.get(b"/api/12345"), Some(&2));
+    ///     assert_eq!(frozen.get(b"/static/css/app.css"), Some(&3));
+    ///
+    ///     // truncated images are rejected
+    ///     assert!(matches!(FrozenRadixMap::<u32>::open(&data[..data.len() - 1]), Err(RadixError::SnapshotInvalid(_))));
+    ///
+    ///     Ok(())
+    /// }
+    /// # #[cfg(not(feature = "glob"))]
+    /// # fn main() {}
+    /// ```
+    #[inline]
+    pub fn open(data: &'a [u8]) -> RadixResult<Self> {
+        Self::open_with_config(data, RadixConfig::default())
+    }
+
+    /// Same as open, the custom matchers are taken from the config, while the separator and
+    /// the flags are restored from the image
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use radixmap::{FrozenRadixMap, RadixMap, RadixResult, rule::RadixConfig};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let digits = |path: &[u8]| -> Option<(usize, Option<Bytes>)> {
+    ///         let len = path.iter().take_while(|c| c.is_ascii_digit()).count();
+    ///         (len > 0).then_some((len, None))
+    ///     };
+    ///
+    ///     let mut map = RadixMap::with_separator("::")?;
+    ///     map.register("digits", digits);
+    ///     map.insert("crate::{id:@digits}", true)?;
+    ///
+    ///     let data = FrozenRadixMap::try_from(&map)?.as_bytes().to_vec();
+    ///
+    ///     // the matcher isn't persisted
+    ///     assert!(FrozenRadixMap::<bool>::open(&data).is_err());
+    ///
+    ///     let mut config = RadixConfig::default();
+    ///     config.register("digits", digits);
+    ///
+    ///     let frozen = FrozenRadixMap::<bool>::open_with_config(&data, config)?;
+    ///
+    ///     assert_eq!(frozen.get(b"crate::12345"), Some(&true));
+    ///     assert_eq!(frozen.get(b"crate::abc"), None);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn open_with_config(data: &'a [u8], mut config: RadixConfig) -> RadixResult<Self> {
+        if data.len() < HEADER + 8 || !data.starts_with(MAGIC) {
+            return Err(RadixError::SnapshotInvalid("not a frozen map"));
+        }
+
+        let body = &data[..data.len() - 8];
+        let mut cursor = Cursor { data: &body[MAGIC.len()..HEADER] };
+        if cursor.u32()? != VERSION {
+            return Err(RadixError::SnapshotInvalid("unsupported version"));
+        }
+
+        let flags = cursor.u32()?;
+        let size = cursor.u32()? as usize;
+        let count = cursor.u32()? as usize;
+        let blob = cursor.u32()? as usize;
+        let sep = cursor.u32()? as usize;
+
+        let nodes = count.checked_mul(RECORD).ok_or(RadixError::SnapshotInvalid("length mismatch"))?;
+        if count == 0 || nodes.checked_add(blob) != Some(body.len() - HEADER) {
+            return Err(RadixError::SnapshotInvalid("length mismatch"));
+        }
+
+        if sep == 0 || sep > blob {
+            return Err(RadixError::SnapshotInvalid("separator is empty"));
+        }
+
+        config.separator = Bytes::copy_from_slice(&body[HEADER + nodes..HEADER + nodes + sep]);
+        config.ignore_case = flags & 1 != 0;
+        config.lenient = flags & 2 != 0;
+
+        let mut map = Self { data: Cow::Borrowed(data), count, blob: HEADER + nodes, size, config, rules: vec![], values: Self::cells(count) };
+        map.check()?;
+        Ok(map)
+    }
+
+    /// Check the checksum of the image and decode every value, which open skips to stay cheap
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{FrozenRadixMap, RadixError, RadixMap, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let map = RadixMap::try_from([("/api/v1", 1u32), ("/api/v2", 2)])?;
+    ///     let mut data = FrozenRadixMap::try_from(&map)?.as_bytes().to_vec();
+    ///
+    ///     assert!(FrozenRadixMap::<u32>::open(&data)?.verify().is_ok());
+    ///
+    ///     // the corrupt image still opens, but fails to verify
+    ///     let last = data.len() - 1;
+    ///     data[last] ^= 0xff;
+    ///
+    ///     let frozen = FrozenRadixMap::<u32>::open(&data)?;
+    ///     assert!(matches!(frozen.verify(), Err(RadixError::SnapshotInvalid(_))));
+    ///
+    ///     // the values can't be decoded as another type
+    ///     let data = FrozenRadixMap::try_from(&map)?.as_bytes().to_vec();
+    ///     assert!(FrozenRadixMap::<u64>::open(&data)?.verify().is_err());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn verify(&self) -> RadixResult<()> {
+        let (body, sum) = self.data.split_at(self.data.len() - 8);
+        if sum != snapshot::checksum(body).to_le_bytes() {
+            return Err(RadixError::SnapshotInvalid("checksum mismatch"));
+        }
+
+        for index in 0..self.count {
+            let node = self.record(index);
+            if node.data[0] != NONE {
+                let data = V::decode(self.slice(node.data))?;
+                let _ = self.values[index].set(Some(data));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The data nodes' count
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{FrozenRadixMap, RadixMap, RadixResult};
+    ///
+    /// # #[cfg(feature = "glob")]
+    /// fn main() -> RadixResult<()> {
+    ///     let map = RadixMap::try_from([("/api", ()), ("/api/:id", ()), ("/api/*", ())])?;
+    ///
+    ///     assert_eq!(FrozenRadixMap::try_from(&map)?.len(), 3);
+    ///     assert_eq!(FrozenRadixMap::try_from(&RadixMap::<()>::new())?.len(), 0);
+    ///
+    ///     Ok(())
+    /// }
+    /// # #[cfg(not(feature = "glob"))]
+    /// # fn main() {}
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    /// Check if the map has no data nodes
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{FrozenRadixMap, RadixMap, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     assert!(FrozenRadixMap::try_from(&RadixMap::<()>::new())?.is_empty());
+    ///     assert!(!FrozenRadixMap::try_from(&RadixMap::try_from([("/api", ())])?)?.is_empty());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// The whole image, save it to share the map between processes
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Retrieve the corresponding data, which is decoded from the image on first read. The value
+    /// which fails to decode is treated as absent
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{FrozenRadixMap, RadixMap, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut map = RadixMap::new();
+    ///     map.insert("/api/v1", 1u32)?;
+    ///     map.insert("/api/v2", 2)?;
+    ///     map.insert("/user/new", 3)?;
+    ///     map.insert("/user/:id", 4)?;
+    ///     map.insert("/page/:n=1", 5)?;
+    ///
+    ///     let frozen = FrozenRadixMap::try_from(&map)?;
+    ///
+    ///     assert_eq!(frozen.get(b"/api/v1"), Some(&1));
+    ///     assert_eq!(frozen.get(b"/api/v2"), Some(&2));
+    ///     assert_eq!(frozen.get(b"/api/v3"), None);
+    ///     assert_eq!(frozen.get(b"/user/new"), Some(&3));
+    ///     assert_eq!(frozen.get(b"/user/newer"), Some(&4));
+    ///     assert_eq!(frozen.get(b"/page"), Some(&5));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn get(&self, path: &[u8]) -> Option<&V> {
+        self.resolve(path, &mut Params::new(), false).and_then(|index| self.value(index))
+    }
+
+    /// Retrieve the corresponding data and collect named captures
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use radixmap::{params::Params, FrozenRadixMap, RadixMap, RadixResult};
+    ///
+    /// # #[cfg(all(feature = "glob", feature = "regex"))]
+    /// fn main() -> RadixResult<()> {
+    ///     let mut map = RadixMap::new();
+    ///     map.insert("/api/v1/user/12345", 1u32)?;
+    ///     map.insert("/api/v2/user/:id", 2)?;
+    ///     map.insert("/api/v3/user/{id:[0-9]+}", 3)?;
+    ///     map.insert("/api/v4/user/*", 4)?;
+    ///     map.insert("/files/:name.:ext", 5)?;
+    ///     map.insert("/archive[/:year=2024]/posts", 6)?;
+    ///
+    ///     let frozen = FrozenRadixMap::try_from(&map)?;
+    ///
+    ///     assert_eq!(frozen.capture(b"/api/v1/user/12345"), (Some(&1), Params::new()));
+    ///     assert_eq!(frozen.capture(b"/api/v2/user/12345"), (Some(&2), Params::from(vec![(Bytes::from("id"), "12345".as_bytes())])));
+    ///     assert_eq!(frozen.capture(b"/api/v3/user/12345"), (Some(&3), Params::from(vec![(Bytes::from("id"), "12345".as_bytes())])));
+    ///     assert_eq!(frozen.capture(b"/api/v3/user/abcde"), (None, Params::new()));
+    ///     assert_eq!(frozen.capture(b"/api/v4/user/12345"), (Some(&4), Params::from(vec![(Bytes::from("*"), "12345".as_bytes())])));
+    ///     assert_eq!(frozen.capture(b"/files/app.tar.gz"), (Some(&5), Params::from(vec![(Bytes::from("name"), "app".as_bytes()), (Bytes::from("ext"), "tar.gz".as_bytes())])));
+    ///     assert_eq!(frozen.capture(b"/archive/posts"), (Some(&6), Params::from(vec![(Bytes::from("year"), "2024".as_bytes())])));
+    ///     assert_eq!(frozen.capture(b"/archive/2023/posts"), (Some(&6), Params::from(vec![(Bytes::from("year"), "2023".as_bytes())])));
+    ///
+    ///     Ok(())
+    /// }
+    /// # #[cfg(not(all(feature = "glob", feature = "regex")))]
+    /// # fn main() {}
+    /// ```
+    pub fn capture<'u>(&self, path: &'u [u8]) -> (Option<&V>, Params<'u>) {
+        let mut capt = Params::new();
+        capt.bind(path);
+
+        match self.resolve(path, &mut capt, true).and_then(|index| self.value(index)) {
+            Some(data) => (Some(data), capt),
+            None => (None, Params::new()),
+        }
+    }
+
+    /// Check if the path matches any data node
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{FrozenRadixMap, RadixMap, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let frozen = FrozenRadixMap::try_from(&RadixMap::try_from([("/api/v1", ()), ("/user/:id", ())])?)?;
+    ///
+    ///     assert!(frozen.contains_key(b"/api/v1"));
+    ///     assert!(frozen.contains_key(b"/user/12345"));
+    ///     assert!(!frozen.contains_key(b"/api"));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn contains_key(&self, path: &[u8]) -> bool {
+        self.resolve(path, &mut Params::new(), false).is_some()
+    }
+
+    /// Iterate the path-data pairs in pre-order, same as RadixMap. The nodes whose value fails to
+    /// decode are skipped, the same as get treats them as absent
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{FrozenRadixMap, RadixMap, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let map = RadixMap::try_from([("/api", 1u8), ("/api/v1", 2), ("/api/v1/user", 3), ("/api/v2", 4), ("/api/:id", 5)])?;
+    ///     let frozen = FrozenRadixMap::try_from(&map)?;
+    ///
+    ///     assert!(frozen.iter().eq(map.iter().map(|(path, data)| (path.as_ref(), data))));
+    ///
+    ///     let mut iter = frozen.iter();
+    ///
+    ///     assert_eq!(iter.next(), Some((b"/api".as_ref(), &1)));
+    ///     assert_eq!(iter.next(), Some((b"/api/v1".as_ref(), &2)));
+    ///     assert_eq!(iter.next(), Some((b"/api/v1/user".as_ref(), &3)));
+    ///     assert_eq!(iter.next(), Some((b"/api/v2".as_ref(), &4)));
+    ///     assert_eq!(iter.next(), Some((b"/api/:id".as_ref(), &5)));
+    ///     assert_eq!(iter.next(), None);
+    ///
+    ///     let map = RadixMap::try_from([("/a", String::from("one")), ("/b", String::from("two")), ("/c", String::from("three"))])?;
+    ///     let mut data = FrozenRadixMap::try_from(&map)?.as_bytes().to_vec();
+    ///
+    ///     // corrupt the value of /b, which is no longer valid utf-8
+    ///     if let Some(pos) = data.windows(3).position(|bytes| bytes == b"two") {
+    ///         data[pos..pos + 3].fill(0xff);
+    ///     }
+    ///
+    ///     let frozen = FrozenRadixMap::<String>::open(&data)?;
+    ///
+    ///     assert_eq!(frozen.len(), 3);
+    ///     assert_eq!(frozen.keys().count(), 3);
+    ///     assert_eq!(frozen.get(b"/b"), None);
+    ///     assert_eq!(frozen.iter().map(|(path, data)| (path, data.as_str())).collect::<Vec<_>>(), [(b"/a".as_ref(), "one"), (b"/c".as_ref(), "three")]);
+    ///     assert_eq!(frozen.values().collect::<Vec<_>>(), ["one", "three"]);
+    ///     assert_eq!(format!("{:?}", frozen).as_str(), r#"{b"/a": "one", b"/c": "three"}"#);
+    ///     assert!(frozen.verify().is_err());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, V> {
+        Iter::from(self)
+    }
+
+    /// Iterator adapter for path
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{FrozenRadixMap, RadixMap, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let frozen = FrozenRadixMap::try_from(&RadixMap::try_from([("/api", ()), ("/api/v1", ()), ("/api/v2", ())])?)?;
+    ///
+    ///     assert_eq!(frozen.keys().collect::<Vec<_>>(), [b"/api".as_ref(), b"/api/v1".as_ref(), b"/api/v2".as_ref()]);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn keys(&self) -> Keys<'_, V> {
+        Keys { iter: self.iter() }
+    }
+
+    /// Iterator adapter for data
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{FrozenRadixMap, RadixMap, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let frozen = FrozenRadixMap::try_from(&RadixMap::try_from([("/api", 0u8), ("/api/v1", 1), ("/api/v2", 2)])?)?;
+    ///
+    ///     assert_eq!(frozen.values().collect::<Vec<_>>(), [&0, &1, &2]);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn values(&self) -> Values<'_, V> {
+        Values { iter: self.iter() }
+    }
+
+    /// Internal use only, check every record once so that lookups can read them unchecked,
+    /// and compile the special rules
+    fn check(&mut self) -> RadixResult<()> {
+        let mut next = 1;
+        let mut total = 0;
+
+        for index in 0..self.count {
+            // every node except the root is claimed by a parent before it
+            if index > 0 && index >= next {
+                return Err(RadixError::SnapshotInvalid("orphan node"));
+            }
+
+            let node = self.record(index);
+            let blob = self.data.len() - 8 - self.blob;
+            for span in [node.frag, node.path, node.data, node.alias] {
+                if span[0] != NONE && !matches!((span[0] as usize).checked_add(span[1] as usize), Some(end) if end <= blob) {
+                    return Err(RadixError::SnapshotInvalid("span out of range"));
+                }
+            }
+
+            if node.frag[0] == NONE || node.path[0] == NONE {
+                return Err(RadixError::SnapshotInvalid("span out of range"));
+            }
+
+            match node.kind {
+                PLAIN | ICASE if node.rule == NONE => {}
+                SPECIAL if node.rule as usize == self.rules.len() => {
+                    let rule = RadixRule::restore(Bytes::copy_from_slice(self.slice(node.frag)), false, false, &self.config)?;
+                    self.rules.push(rule);
+                }
+                _ => return Err(RadixError::SnapshotInvalid("unknown rule")),
+            }
+
+            if index == 0 && (node.kind == SPECIAL || node.frag[1] != 0) {
+                return Err(RadixError::SnapshotInvalid("root is not empty"));
+            }
+
+            // the regular node must be reachable by its first byte
+            let first = self.slice(node.frag).first().map(|&byte| match node.kind {
+                ICASE => byte.to_ascii_lowercase() as u32,
+                _ => byte as u32,
+            });
+
+            if index > 0 && node.kind != SPECIAL && first != Some(node.key) {
+                return Err(RadixError::SnapshotInvalid("regular key mismatch"));
+            }
+
+            // the children of all nodes are laid out back to back
+            let children = node.regular as usize + node.special as usize;
+            if node.first as usize != next || next + children > self.count {
+                return Err(RadixError::SnapshotInvalid("children out of range"));
+            }
+
+            let mut prev = None;
+
+            for child in next..next + node.regular as usize {
+                let child = self.record(child);
+                if child.kind == SPECIAL || child.key == NONE || prev >= Some(child.key) {
+                    return Err(RadixError::SnapshotInvalid("regular key mismatch"));
+                }
+
+                prev = Some(child.key);
+            }
+
+            for child in next + node.regular as usize..next + children {
+                let child = self.record(child);
+                if child.kind != SPECIAL || child.key != NONE {
+                    return Err(RadixError::SnapshotInvalid("special key mismatch"));
+                }
+            }
+
+            next += children;
+
+            if node.alias[0] != NONE {
+                let mut cursor = Cursor { data: self.slice(node.alias) };
+                cursor.slice()?;
+
+                for _ in 0..cursor.u32()? {
+                    cursor.slice()?;
+                    cursor.slice()?;
+                }
+
+                if !cursor.data.is_empty() {
+                    return Err(RadixError::SnapshotInvalid("trailing bytes"));
+                }
+            }
+
+            if node.data[0] != NONE {
+                total += 1;
+            }
+        }
+
+        match total == self.size {
+            true => Ok(()),
+            false => Err(RadixError::SnapshotInvalid("size mismatch")),
+        }
+    }
+}
+
+impl<'a, V> FrozenRadixMap<'a, V> {
+    /// Internal use only, read the record of a node
+    #[inline]
+    fn record(&self, index: usize) -> Record {
+        let data = &self.data[HEADER + index * RECORD..HEADER + (index + 1) * RECORD];
+        let word = |pos: usize| u32::from_le_bytes([data[pos * 4], data[pos * 4 + 1], data[pos * 4 + 2], data[pos * 4 + 3]]);
+
+        Record {
+            key: word(0),
+            kind: word(1),
+            rule: word(2),
+            frag: [word(3), word(4)],
+            path: [word(5), word(6)],
+            data: [word(7), word(8)],
+            alias: [word(9), word(10)],
+            first: word(11),
+            regular: word(12),
+            special: word(13),
+        }
+    }
+
+    /// Internal use only, the bytes of a span in the blob
+    #[inline]
+    fn slice(&self, span: [u32; 2]) -> &[u8] {
+        let from = self.blob + span[0] as usize;
+        &self.data[from..from + span[1] as usize]
+    }
+
+    /// Internal use only, the compiled rule of a special node
+    #[inline]
+    fn rule(&self, node: &Record) -> Option<&RadixRule> {
+        match node.kind {
+            SPECIAL => self.rules.get(node.rule as usize),
+            _ => None,
+        }
+    }
+
+    /// Internal use only, the empty cells of the values
+    #[inline]
+    fn cells(count: usize) -> Vec<Lazy<V>> {
+        (0..count).map(|_| Lazy::new()).collect()
+    }
+
+    /// Internal use only, the node which the lookup walks through
+    #[inline]
+    fn slot(&self, index: usize) -> Slot<'_, 'a, V> {
+        Slot { map: self, index, node: self.record(index) }
+    }
+
+    /// Internal use only, decode the data of a node on first read
+    #[inline]
+    fn value(&self, index: usize) -> Option<&V> where V: RadixCodec {
+        let node = self.record(index);
+        match node.data[0] {
+            NONE => None,
+            _ => self.values[index].get_or_init(|| V::decode(self.slice(node.data)).ok()).as_ref(),
+        }
+    }
+
+    /// Internal use only, find the data node of the path and follow the alias
+    #[inline]
+    fn resolve<'u>(&self, path: &'u [u8], capture: &mut Params<'u>, enable: bool) -> Option<usize> {
+        let root = self.slot(0);
+        let node = root.walk(path, true, false, capture, enable, None)?;
+        node.follow(root, capture, None).map(|node| node.index)
+    }
+
+    /// Internal use only, the regular child keyed by the byte, the uppercase byte falls back
+    /// to the lowercase key
+    #[inline]
+    fn regular(&self, node: &Record, byte: u8) -> Option<usize> {
+        let first = node.first as usize;
+        let search = |key: u32| {
+            let (mut lo, mut hi) = (first, first + node.regular as usize);
+
+            while lo < hi {
+                let mid = lo + (hi - lo) / 2;
+                let pos = HEADER + mid * RECORD;
+                let cur = u32::from_le_bytes([self.data[pos], self.data[pos + 1], self.data[pos + 2], self.data[pos + 3]]);
+
+                match cur.cmp(&key) {
+                    Ordering::Less => lo = mid + 1,
+                    Ordering::Greater => hi = mid,
+                    Ordering::Equal => return Some(mid),
+                }
+            }
+
+            None
+        };
+
+        search(byte as u32).or_else(|| match byte.is_ascii_uppercase() {
+            true => search(byte.to_ascii_lowercase() as u32),
+            false => None,
+        })
+    }
+}
+
+// -----------------------------------------------------------------------------
+
+/// Flatten the tree of a map, the nodes are stored in level-order
+///
+/// # Examples
+///
+/// ```
+/// use radixmap::{FrozenRadixMap, RadixMap, RadixResult};
+///
+/// fn main() -> RadixResult<()> {
+///     let mut map = RadixMap::with_config(radixmap::rule::RadixConfig { ignore_case: true, ..Default::default() });
+///     map.insert("/Api/v1", String::from("v1"))?;
+///     map.insert("/api/:id", String::from("id"))?;
+///
+///     let frozen = FrozenRadixMap::try_from(&map)?;
+///
+///     assert_eq!(frozen.get(b"/API/V1").map(String::as_str), Some("v1"));
+///     assert_eq!(frozen.get(b"/api/12345").map(String::as_str), Some("id"));
+///
+///     Ok(())
+/// }
+/// ```
+impl<V: RadixCodec> TryFrom<&RadixMap<V>> for FrozenRadixMap<'static, V> {
+    type Error = RadixError;
+
+    fn try_from(map: &RadixMap<V>) -> RadixResult<Self> {
+        let mut order = vec![(NONE, &map.root)];
+        let mut nodes = Vec::new();
+        let mut blob = map.config.separator.to_vec();
+        let mut rules = vec![];
+        let mut index = 0;
+
+        while let Some(&(key, node)) = order.get(index) {
+            index += 1;
+
+            let first = order.len();
+            order.extend(node.next.regular.iter().map(|(key, next)| (key as u32, next)));
+            order.extend(node.next.special.values().map(|next| (NONE, next)));
+
+            let (kind, rule) = match (node.rule.is_special(), node.rule.is_icase()) {
+                (true, _) => {
+                    rules.push(node.rule.clone());
+                    (SPECIAL, word(rules.len() - 1)?)
+                }
+                (false, false) => (PLAIN, NONE),
+                (false, true) => (ICASE, NONE),
+            };
+
+            let frag = span(&mut blob, |buf| buf.extend_from_slice(node.rule.origin()))?;
+            let path = span(&mut blob, |buf| buf.extend_from_slice(&node.path))?;
+
+            let data = match &node.data {
+                Some(data) => span(&mut blob, |buf| data.encode(buf))?,
+                None => [NONE, 0],
+            };
+
+            let alias = match &node.alias {
+                Some(alias) => span(&mut blob, |buf| {
+                    snapshot::bytes(buf, &alias.link);
+                    buf.extend_from_slice(&(alias.defaults.len() as u32).to_le_bytes());
+
+                    for (name, value) in &alias.defaults {
+                        snapshot::bytes(buf, name);
+                        snapshot::bytes(buf, value);
+                    }
+                })?,
+                None => [NONE, 0],
+            };
+
+            let record = [
+                key, kind, rule,
+                frag[0], frag[1], path[0], path[1], data[0], data[1], alias[0], alias[1],
+                word(first)?, word(node.next.regular.len())?, word(node.next.special.len())?,
+            ];
+
+            for word in record {
+                nodes.extend_from_slice(&word.to_le_bytes());
+            }
+        }
+
+        let mut data = Vec::with_capacity(HEADER + nodes.len() + blob.len() + 8);
+
+        data.extend_from_slice(MAGIC);
+        data.extend_from_slice(&VERSION.to_le_bytes());
+        data.extend_from_slice(&((map.config.ignore_case as u32) | ((map.config.lenient as u32) << 1)).to_le_bytes());
+        data.extend_from_slice(&word(map.size)?.to_le_bytes());
+        data.extend_from_slice(&word(order.len())?.to_le_bytes());
+        data.extend_from_slice(&word(blob.len())?.to_le_bytes());
+        data.extend_from_slice(&word(map.config.separator.len())?.to_le_bytes());
+        data.extend_from_slice(&nodes);
+        data.extend_from_slice(&blob);
+
+        let sum = snapshot::checksum(&data);
+        data.extend_from_slice(&sum.to_le_bytes());
+
+        let blob = HEADER + nodes.len();
+        Ok(Self { data: Cow::Owned(data), count: order.len(), blob, size: map.size, config: map.config.clone(), rules, values: Self::cells(order.len()) })
+    }
+}
+
+/// Clone trait, the clone decodes the values once more
+impl<'a, V> Clone for FrozenRadixMap<'a, V> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            data: self.data.clone(),
+            count: self.count,
+            blob: self.blob,
+            size: self.size,
+            config: self.config.clone(),
+            rules: self.rules.clone(),
+            values: Self::cells(self.count),
+        }
+    }
+}
+
+/// Debug trait
+///
+/// # Examples
+///
+/// ```
+/// use radixmap::{FrozenRadixMap, RadixMap, RadixResult};
+///
+/// fn main() -> RadixResult<()> {
+///     let frozen = FrozenRadixMap::try_from(&RadixMap::try_from([("/api/v1", 1), ("/api/v2", 2)])?)?;
+///
+///     assert_eq!(format!("{:?}", frozen).as_str(), r#"{b"/api/v1": 1, b"/api/v2": 2}"#);
+///
+///     Ok(())
+/// }
+/// ```
+impl<'a, V: RadixCodec + Debug> Debug for FrozenRadixMap<'a, V> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_map().entries(self.iter().map(|(path, data)| (Bytes::copy_from_slice(path), data))).finish()
+    }
+}
+
+/// Internal use only, narrow the offset or length down to a word
+#[inline]
+fn word(value: usize) -> RadixResult<u32> {
+    match u32::try_from(value) {
+        Ok(value) if value != NONE => Ok(value),
+        _ => Err(RadixError::SnapshotInvalid("frozen map exceeds 4 GiB")),
+    }
+}
+
+/// Internal use only, append the bytes written by func to the blob and return their span
+#[inline]
+fn span(blob: &mut Vec<u8>, func: impl FnOnce(&mut Vec<u8>)) -> RadixResult<[u32; 2]> {
+    let from = blob.len();
+    func(blob);
+    Ok([word(from)?, word(blob.len() - from)?])
+}
+
+// -----------------------------------------------------------------------------
+
+/// Internal use only, the words of a node record
+#[derive(Clone, Copy)]
+struct Record {
+    /// The first byte of a regular node, or NONE for a special node
+    key: u32,
+
+    /// PLAIN, ICASE or SPECIAL
+    kind: u32,
+
+    /// The index of the compiled special rule
+    rule: u32,
+
+    /// The offset and length of the fragment
+    frag: [u32; 2],
+
+    /// The offset and length of the key of the data node
+    path: [u32; 2],
+
+    /// The offset and length of the encoded data
+    data: [u32; 2],
+
+    /// The offset and length of the alias
+    alias: [u32; 2],
+
+    /// The index of the first child
+    first: u32,
+
+    /// The number of regular children, which come first
+    regular: u32,
+
+    /// The number of special children
+    special: u32,
+}
+
+// -----------------------------------------------------------------------------
+
+/// Internal use only, a record along with the map it belongs to, walked by the same lookup as
+/// RadixNode
+struct Slot<'n, 'a, V> {
+    map: &'n FrozenRadixMap<'a, V>,
+    index: usize,
+    node: Record,
+}
+
+impl<'n, 'a, V> Clone for Slot<'n, 'a, V> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'n, 'a, V> Copy for Slot<'n, 'a, V> {}
+
+impl<'n, 'a, V> RadixWalk for Slot<'n, 'a, V> {
+    type Specials = Specials<'n, 'a, V>;
+
+    #[inline]
+    fn rule(&self) -> Option<&RadixRule> {
+        self.map.rule(&self.node)
+    }
+
+    #[inline]
+    fn size(&self) -> usize {
+        self.node.frag[1] as usize
+    }
+
+    #[inline]
+    fn longest<'u>(&self, path: &'u [u8], raw: bool) -> Option<(&'u [u8], Option<Bytes>)> {
+        let frag = self.map.slice(self.node.frag);
+        let len = match self.node.kind {
+            PLAIN if path.starts_with(frag) => frag.len(),
+            PLAIN => frag.iter().zip(path).take_while(|(a, b)| a == b).count(),
+            ICASE => frag.iter().zip(path).take_while(|(a, b)| a.eq_ignore_ascii_case(b)).count(),
+            _ => return self.rule()?.normalize(path, raw),
+        };
+
+        Some((&path[..len], None))
+    }
+
+    #[inline]
+    fn regular(&self, byte: u8) -> Option<Self> {
+        self.map.regular(&self.node, byte).map(|index| self.map.slot(index))
+    }
+
+    #[inline]
+    fn specials(&self) -> Self::Specials {
+        let first = self.node.first as usize + self.node.regular as usize;
+        Specials { map: self.map, range: first..first + self.node.special as usize }
+    }
+
+    #[inline]
+    fn has_regular(&self) -> bool {
+        self.node.regular != 0
+    }
+
+    #[inline]
+    fn has_special(&self) -> bool {
+        self.node.special != 0
+    }
+
+    #[inline]
+    fn has_data(&self) -> bool {
+        self.node.data[0] != NONE
+    }
+
+    #[inline]
+    fn has_alias(&self) -> bool {
+        self.node.data[0] == NONE && self.node.alias[0] != NONE
+    }
+
+    fn alias(&self, capture: &mut Params<'_>) -> Option<&[u8]> {
+        if self.node.alias[0] == NONE {
+            return None;
+        }
+
+        let mut cursor = Cursor { data: self.map.slice(self.node.alias) };
+        let link = cursor.slice().ok()?;
+
+        for _ in 0..cursor.u32().ok()? {
+            let name = cursor.bytes().ok()?;
+            capture.push_owned(name, cursor.bytes().ok()?);
+        }
+
+        Some(link)
+    }
+}
+
+// -----------------------------------------------------------------------------
+
+/// Internal use only, the special children of a record
+struct Specials<'n, 'a, V> {
+    map: &'n FrozenRadixMap<'a, V>,
+    range: Range<usize>,
+}
+
+impl<'n, 'a, V> Iterator for Specials<'n, 'a, V> {
+    type Item = Slot<'n, 'a, V>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.range.next().map(|index| self.map.slot(index))
+    }
+}
+
+// -----------------------------------------------------------------------------
+
+/// The iterator for frozen map, in pre-order
+pub struct Iter<'n, V> {
+    base: &'n FrozenRadixMap<'n, V>,
+    stack: Vec<usize>,
+}
+
+impl<'n, V> Iter<'n, V> {
+    /// Internal use only, the next data node
+    fn next_node(&mut self) -> Option<usize> {
+        loop {
+            let index = self.stack.pop()?;
+            let node = self.base.record(index);
+
+            let first = node.first as usize;
+            self.stack.extend((first..first + node.regular as usize + node.special as usize).rev());
+
+            if node.data[0] != NONE {
+                return Some(index);
+            }
+        }
+    }
+}
+
+impl<'n, 'a: 'n, V> From<&'n FrozenRadixMap<'a, V>> for Iter<'n, V> {
+    #[inline]
+    fn from(base: &'n FrozenRadixMap<'a, V>) -> Self {
+        Self { base, stack: vec![0] }
+    }
+}
+
+impl<'n, V: RadixCodec> Iterator for Iter<'n, V> {
+    type Item = (&'n [u8], &'n V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let index = self.next_node()?;
+            if let Some(data) = self.base.value(index) {
+                return Some((self.base.slice(self.base.record(index).path), data));
+            }
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+
+/// The path adapter, the values are not decoded
+pub struct Keys<'n, V> {
+    iter: Iter<'n, V>,
+}
+
+impl<'n, V> Iterator for Keys<'n, V> {
+    type Item = &'n [u8];
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.iter.next_node()?;
+        Some(self.iter.base.slice(self.iter.base.record(index).path))
+    }
+}
+
+// -----------------------------------------------------------------------------
+
+/// The data adapter
+pub struct Values<'n, V> {
+    iter: Iter<'n, V>,
+}
+
+impl<'n, V: RadixCodec> Iterator for Values<'n, V> {
+    type Item = &'n V;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(_, data)| data)
+    }
+}
@@ -1,17 +1,26 @@
 #![doc = include_str!("../README.md")]
+#![cfg_attr(not(feature = "std"), no_std)]
 #![warn(missing_docs)]
 #![deny(clippy::unwrap_used)]
 #![deny(clippy::expect_used)]
 #![deny(clippy::let_underscore_future)]
 
+extern crate alloc;
+
 pub mod map;
 pub mod set;
+pub mod plain;
+pub mod frozen;
 
 pub mod defs;
 pub mod node;
 pub mod pack;
+pub mod params;
 pub mod rule;
+pub mod snapshot;
 
 pub use map::{RadixMap};
 pub use set::{RadixSet};
+pub use plain::{PlainRadixMap, PlainRadixSet};
+pub use frozen::{FrozenRadixMap};
 pub use defs::{RadixError, RadixResult};
\ No newline at end of file
@@ -1,14 +1,25 @@
 //! Radix map implementation
 use super::defs::*;
-use super::node::{self, RadixNode};
+use core::ptr::NonNull;
+use super::node::{self, RadixNode, RadixWalk, Track};
+use super::params::{Params, ParamsBuf};
+use super::rule::{RadixConfig, RadixMatcher, RadixRule};
+#[cfg(feature = "std")]
+use super::snapshot::{self, RadixCodec};
 
 /// The radix map where the key is Bytes and the value is arbitrary data
 pub struct RadixMap<V> {
     /// The root node, always empty
-    root: RadixNode<V>,
+    pub(crate) root: RadixNode<V>,
 
     /// The number of data nodes
-    size: usize,
+    pub(crate) size: usize,
+
+    /// The options of the rules
+    pub(crate) config: RadixConfig,
+
+    /// The conflicts recorded in the lenient mode
+    warnings: Vec<RadixError>,
 }
 
 impl<V> RadixMap<V> {
@@ -18,6 +29,219 @@ impl<V> RadixMap<V> {
         Default::default()
     }
 
+    /// Create an empty map whose segments are divided by a custom separator, either a single
+    /// byte or a multi-byte sequence. Params and captures end at the separator
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use radixmap::{params::Params, RadixMap, RadixResult};
+    ///
+    /// # #[cfg(feature = "glob")]
+    /// fn main() -> RadixResult<()> {
+    ///     let mut map = RadixMap::with_separator(".")?;
+    ///     map.insert("db.:name.host", "host")?;
+    ///     map.insert("db.:name.port", "port")?;
+    ///
+    ///     assert_eq!(map.capture(b"db.main.host"), (Some(&"host"), Params::from(vec![(Bytes::from("name"), "main".as_bytes())])));
+    ///     assert_eq!(map.capture(b"db.main.port"), (Some(&"port"), Params::from(vec![(Bytes::from("name"), "main".as_bytes())])));
+    ///     assert_eq!(map.get(b"db.main/x.host"), Some(&"host"));
+    ///
+    ///     let mut map = RadixMap::with_separator("::")?;
+    ///     map.insert("crate:::module::*", "glob")?;
+    ///     map.insert("crate::util", "util")?;
+    ///
+    ///     assert_eq!(map.capture(b"crate::net::tcp::connect"), (Some(&"glob"), Params::from(vec![(Bytes::from("module"), "net".as_bytes()), (Bytes::from("*"), "tcp::connect".as_bytes())])));
+    ///     assert_eq!(map.get(b"crate::util"), Some(&"util"));
+    ///
+    ///     assert!(RadixMap::<()>::with_separator("").is_err());
+    ///
+    ///     Ok(())
+    /// }
+    /// # #[cfg(not(feature = "glob"))]
+    /// # fn main() {}
+    /// ```
+    #[inline]
+    pub fn with_separator(sep: impl AsRef<[u8]>) -> RadixResult<Self> {
+        Ok(Self::with_config(RadixConfig::with_separator(sep)?))
+    }
+
+    /// Create an empty map with the options of the rules, e.g. ignore the ascii case so that
+    /// legacy urls in different cases hit the same route, while the keys stay as inserted
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use radixmap::{rule::RadixConfig, RadixMap, RadixResult};
+    ///
+    /// # #[cfg(all(feature = "glob", feature = "regex"))]
+    /// fn main() -> RadixResult<()> {
+    ///     let mut map = RadixMap::with_config(RadixConfig { ignore_case: true, ..Default::default() });
+    ///     map.insert("/Products/List", "list")?;
+    ///     map.insert("/products/:id", "item")?;
+    ///     map.insert("/static/*.HTML", "html")?;
+    ///     map.insert("/user/{name:[a-z]+}", "user")?;
+    ///
+    ///     assert_eq!(map.get(b"/Products/List"), Some(&"list"));
+    ///     assert_eq!(map.get(b"/products/list"), Some(&"list"));
+    ///     assert_eq!(map.get(b"/PRODUCTS/12345"), Some(&"item"));
+    ///     assert_eq!(map.get(b"/Static/index.html"), Some(&"html"));
+    ///     assert_eq!(map.get(b"/user/Chensoft"), Some(&"user"));
+    ///     assert_eq!(map.raw(b"/Products/List"), Some(&"list"));
+    ///     assert_eq!(map.keys().next(), Some(&Bytes::from("/Products/List")));
+    ///
+    ///     Ok(())
+    /// }
+    /// # #[cfg(not(all(feature = "glob", feature = "regex")))]
+    /// # fn main() {}
+    /// ```
+    #[inline]
+    pub fn with_config(config: RadixConfig) -> Self {
+        Self { root: RadixNode::default(), size: 0, config, warnings: vec![] }
+    }
+
+    /// The seed which deserializes a map with the options of the rules. The config isn't
+    /// serialized along with the patterns, so the map is restored with the one given here
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde::de::DeserializeSeed;
+    /// use radixmap::{rule::RadixConfig, RadixMap, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut map = RadixMap::with_config(RadixConfig { ignore_case: true, ..Default::default() });
+    ///     map.insert("/Api/v1", 1u32)?;
+    ///     map.insert("/api/:id", 2)?;
+    ///
+    ///     let json = serde_json::to_string(&map).unwrap_or_default();
+    ///
+    ///     let mut de = serde_json::Deserializer::from_str(&json);
+    ///     let seed = RadixMap::<u32>::seed(RadixConfig { ignore_case: true, ..Default::default() });
+    ///     let map = seed.deserialize(&mut de).unwrap_or_default();
+    ///
+    ///     assert_eq!(map.get(b"/API/V1"), Some(&1));
+    ///     assert_eq!(map.get(b"/API/12345"), Some(&2));
+    ///
+    ///     // the default config is case-sensitive
+    ///     let map: RadixMap<u32> = serde_json::from_str(&json).unwrap_or_default();
+    ///
+    ///     assert_eq!(map.get(b"/API/V1"), None);
+    ///     assert_eq!(map.get(b"/Api/v1"), Some(&1));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "serde")]
+    #[inline]
+    pub fn seed(config: RadixConfig) -> Seed<V> {
+        Seed { config, kind: core::marker::PhantomData }
+    }
+
+    /// Register a custom matcher, the paths inserted afterwards refer to it as {name:@matcher}.
+    /// The matcher decides how many bytes it takes and may normalize the capture
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use radixmap::{rule::RadixMatcher, RadixMap, RadixResult};
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    ///
+    /// static CALLS: AtomicUsize = AtomicUsize::new(0);
+    ///
+    /// struct Date;
+    ///
+    /// impl RadixMatcher for Date {
+    ///     fn matches(&self, path: &[u8]) -> Option<(usize, Option<Bytes>)> {
+    ///         CALLS.fetch_add(1, Ordering::Relaxed);
+    ///         let data = std::str::from_utf8(path.get(..10)?).ok()?;
+    ///         let (year, rest) = data.split_once('-')?;
+    ///         let (month, day) = rest.split_once('-')?;
+    ///         let (_, month, day) = (year.parse::<u16>().ok()?, month.parse::<u8>().ok()?, day.parse::<u8>().ok()?);
+    ///
+    ///         match (1..=12).contains(&month) && (1..=31).contains(&day) {
+    ///             true => Some((10, None)),
+    ///             false => None,
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut map = RadixMap::new();
+    ///     map.register("date", Date);
+    ///     map.register("hex32", |path: &[u8]| -> Option<(usize, Option<Bytes>)> {
+    ///         let data = path.get(..32)?;
+    ///         match data.iter().all(u8::is_ascii_hexdigit) {
+    ///             true => Some((32, Some(Bytes::from(data.to_ascii_lowercase())))),
+    ///             false => None,
+    ///         }
+    ///     });
+    ///
+    ///     map.insert("/logs/{d:@date}", "logs")?;
+    ///     map.insert("/blobs/{h:@hex32}", "blob")?;
+    ///     map.insert("/blobs/:name", "name")?;
+    ///
+    ///     assert_eq!(map.get(b"/logs/2024-05-01"), Some(&"logs"));
+    ///     assert_eq!(map.get(b"/logs/2024-13-01"), None);
+    ///
+    ///     // the matcher runs once per lookup, captured or not
+    ///     CALLS.store(0, Ordering::Relaxed);
+    ///     assert_eq!(map.capture(b"/logs/2024-05-01").1.get("d"), Some(b"2024-05-01".as_ref()));
+    ///     assert_eq!(CALLS.load(Ordering::Relaxed), 1);
+    ///
+    ///     let (data, params) = map.capture(b"/blobs/0123456789ABCDEF0123456789ABCDEF");
+    ///     assert_eq!(data, Some(&"blob"));
+    ///     assert_eq!(params.get("h"), Some(b"0123456789abcdef0123456789abcdef".as_ref()));
+    ///
+    ///     assert_eq!(map.get(b"/blobs/readme"), Some(&"name"));
+    ///     assert_eq!(map.raw(b"/blobs/{h:@hex32}"), Some(&"blob"));
+    ///     assert!(map.insert("/users/{id:@ulid}", "user").is_err());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn register(&mut self, name: impl Into<Bytes>, matcher: impl RadixMatcher + 'static) {
+        self.config.register(name, matcher);
+    }
+
+    /// The conflicts recorded instead of being rejected, see RadixConfig::lenient
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{rule::RadixConfig, RadixError, RadixMap, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut map = RadixMap::new();
+    ///     map.insert("/users/:id", "id")?;
+    ///
+    ///     // the param matches the same segments as the existing one
+    ///     assert!(matches!(map.insert("/users/:name", "name"), Err(RadixError::Conflict { .. })));
+    ///     assert_eq!(map.len(), 1);
+    ///
+    ///     let mut map = RadixMap::with_config(RadixConfig { lenient: true, ..Default::default() });
+    ///     map.insert("/users/:id", "id")?;
+    ///     map.insert("/users/:name", "name")?;
+    ///
+    ///     assert_eq!(map.len(), 2);
+    ///     assert_eq!(map.get(b"/users/12345"), Some(&"id"));
+    ///     assert_eq!(map.warnings().iter().map(|err| err.to_string()).collect::<Vec<_>>(), ["route '/users/:name' conflicts with '/users/:id'"]);
+    ///
+    ///     map.clear();
+    ///     assert!(map.warnings().is_empty());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn warnings(&self) -> &[RadixError] {
+        &self.warnings
+    }
+
     /// The data nodes' count, note that RadixMap ignores empty nodes
     ///
     /// # Examples
@@ -86,7 +310,7 @@ impl<V> RadixMap<V> {
     /// ```
     #[inline]
     pub fn get(&self, path: &[u8]) -> Option<&V> {
-        self.root.lookup(path, true, false, &mut vec![], false).and_then(|node| node.data.as_ref())
+        self.resolve(path, false, &mut Params::new(), false).and_then(|node| node.data.as_ref())
     }
 
     /// Retrieve the corresponding mutable data
@@ -118,7 +342,7 @@ impl<V> RadixMap<V> {
     /// ```
     #[inline]
     pub fn get_mut(&mut self, path: &[u8]) -> Option<&mut V> {
-        self.root.lookup_mut(path, true, false, &mut vec![], false).and_then(|node| node.data.as_mut())
+        self.resolve_mut(path, false, &mut Params::new(), false).and_then(|node| node.data.as_mut())
     }
 
     /// Retrieve the corresponding data via raw path
@@ -128,6 +352,7 @@ impl<V> RadixMap<V> {
     /// ```
     /// use radixmap::{RadixMap, RadixResult};
     ///
+    /// # #[cfg(all(feature = "glob", feature = "regex"))]
     /// fn main() -> RadixResult<()> {
     ///     let mut map = RadixMap::new();
     ///     map.insert("/plain/1", 1)?;
@@ -140,12 +365,21 @@ impl<V> RadixMap<V> {
     ///     assert_eq!(map.raw(b"/glob/*"), Some(&3));
     ///     assert_eq!(map.raw(br"/regex/{id:\d+}"), Some(&4));
     ///
+    ///     // the escaped pattern and its canonical form address the same node
+    ///     map.insert(r"/v1/projects/p1\:batchGet", 5)?;
+    ///
+    ///     assert_eq!(map.raw(br"/v1/projects/p1\:batchGet"), Some(&5));
+    ///     assert_eq!(map.raw(b"/v1/projects/p1:batchGet"), Some(&5));
+    ///     assert_eq!(map.get(b"/v1/projects/p1:batchGet"), Some(&5));
+    ///
     ///     Ok(())
     /// }
+    /// # #[cfg(not(all(feature = "glob", feature = "regex")))]
+    /// # fn main() {}
     /// ```
     #[inline]
     pub fn raw(&self, path: &[u8]) -> Option<&V> {
-        self.root.lookup(path, true, true, &mut vec![], false).and_then(|node| node.data.as_ref())
+        self.resolve(path, true, &mut Params::new(), false).and_then(|node| node.data.as_ref())
     }
 
     /// Retrieve the corresponding mutable data via raw path
@@ -155,6 +389,7 @@ impl<V> RadixMap<V> {
     /// ```
     /// use radixmap::{RadixMap, RadixResult};
     ///
+    /// # #[cfg(all(feature = "glob", feature = "regex"))]
     /// fn main() -> RadixResult<()> {
     ///     let mut map = RadixMap::new();
     ///     map.insert("/plain/1", 1)?;
@@ -190,10 +425,12 @@ impl<V> RadixMap<V> {
     ///
     ///     Ok(())
     /// }
+    /// # #[cfg(not(all(feature = "glob", feature = "regex")))]
+    /// # fn main() {}
     /// ```
     #[inline]
     pub fn raw_mut(&mut self, path: &[u8]) -> Option<&mut V> {
-        self.root.lookup_mut(path, true, true, &mut vec![], false).and_then(|node| node.data.as_mut())
+        self.resolve_mut(path, true, &mut Params::new(), false).and_then(|node| node.data.as_mut())
     }
 
     /// Retrieve the corresponding data and collect named captures
@@ -202,8 +439,9 @@ impl<V> RadixMap<V> {
     ///
     /// ```
     /// use bytes::Bytes;
-    /// use radixmap::{RadixMap, RadixResult};
+    /// use radixmap::{params::Params, RadixMap, RadixResult};
     ///
+    /// # #[cfg(all(feature = "glob", feature = "regex"))]
     /// fn main() -> RadixResult<()> {
     ///     let mut map = RadixMap::new();
     ///     map.insert("/api/v1/user/12345", "user1")?;
@@ -214,33 +452,206 @@ impl<V> RadixMap<V> {
     ///     map.insert("/blog/:date/{author:[^/]+}/*.php", "blog")?;
     ///     map.insert("/blog/:date/{author:[^/]+}/:date/*.html", "blog")?;
     ///
-    ///     assert_eq!(map.capture(b"/api/v1/user/12345"), (Some(&"user1"), vec![]));
-    ///     assert_eq!(map.capture(b"/api/v2/user/12345"), (Some(&"user2"), vec![(Bytes::from("id"), "12345".as_bytes())]));
-    ///     assert_eq!(map.capture(b"/api/v2/user/"), (None, vec![]));
-    ///     assert_eq!(map.capture(b"/api/v3/user/12345"), (Some(&"user3"), vec![(Bytes::from("id"), "12345".as_bytes())]));
-    ///     assert_eq!(map.capture(b"/api/v4/user/12345"), (None, vec![]));
-    ///     assert_eq!(map.capture(b"/api/v5/user/12345"), (Some(&"user5"), vec![(Bytes::from("*"), "12345".as_bytes())]));
-    ///     assert_eq!(map.capture(b"/api/v5/user/"), (Some(&"user5"), vec![(Bytes::from("*"), "".as_bytes())]));
-    ///     assert_eq!(map.capture(b"/api/v6"), (None, vec![]));
-    ///     assert_eq!(map.capture(b"/blog/2024-04-10/chensoft/index.asp"), (None, vec![]));
-    ///     assert_eq!(map.capture(b"/blog/2024-04-10/chensoft/index.php"), (Some(&"blog"), vec![(Bytes::from("date"), "2024-04-10".as_bytes()), (Bytes::from("author"), "chensoft".as_bytes()), (Bytes::from("*"), "index.php".as_bytes())]));
-    ///     assert_eq!(map.capture(b"/blog/2024-04-10/chensoft/2024-05-01/index.html"), (Some(&"blog"), vec![(Bytes::from("date"), "2024-04-10".as_bytes()), (Bytes::from("author"), "chensoft".as_bytes()), (Bytes::from("date"), "2024-05-01".as_bytes()), (Bytes::from("*"), "index.html".as_bytes())]));
+    ///     assert_eq!(map.capture(b"/api/v1/user/12345"), (Some(&"user1"), Params::new()));
+    ///     assert_eq!(map.capture(b"/api/v2/user/12345"), (Some(&"user2"), Params::from(vec![(Bytes::from("id"), "12345".as_bytes())])));
+    ///     assert_eq!(map.capture(b"/api/v2/user/"), (None, Params::new()));
+    ///     assert_eq!(map.capture(b"/api/v3/user/12345"), (Some(&"user3"), Params::from(vec![(Bytes::from("id"), "12345".as_bytes())])));
+    ///     assert_eq!(map.capture(b"/api/v4/user/12345"), (None, Params::new()));
+    ///     assert_eq!(map.capture(b"/api/v5/user/12345"), (Some(&"user5"), Params::from(vec![(Bytes::from("*"), "12345".as_bytes())])));
+    ///     assert_eq!(map.capture(b"/api/v5/user/"), (Some(&"user5"), Params::from(vec![(Bytes::from("*"), "".as_bytes())])));
+    ///     assert_eq!(map.capture(b"/api/v6"), (None, Params::new()));
+    ///     assert_eq!(map.capture(b"/blog/2024-04-10/chensoft/index.asp"), (None, Params::new()));
+    ///     assert_eq!(map.capture(b"/blog/2024-04-10/chensoft/index.php"), (Some(&"blog"), Params::from(vec![(Bytes::from("date"), "2024-04-10".as_bytes()), (Bytes::from("author"), "chensoft".as_bytes()), (Bytes::from("*"), "index.php".as_bytes())])));
+    ///     assert_eq!(map.capture(b"/blog/2024-04-10/chensoft/2024-05-01/index.html"), (Some(&"blog"), Params::from(vec![(Bytes::from("date"), "2024-04-10".as_bytes()), (Bytes::from("author"), "chensoft".as_bytes()), (Bytes::from("date"), "2024-05-01".as_bytes()), (Bytes::from("*"), "index.html".as_bytes())])));
+    ///
+    ///     // fall back to the param if the plain branch dead-ends
+    ///     map.insert("/user/new", "new")?;
+    ///     map.insert("/user/:id", "user")?;
+    ///
+    ///     assert_eq!(map.capture(b"/user/new"), (Some(&"new"), Params::new()));
+    ///     assert_eq!(map.capture(b"/user/newer"), (Some(&"user"), Params::from(vec![(Bytes::from("id"), "newer".as_bytes())])));
+    ///
+    ///     // typed params fall through to the siblings if the constraint fails
+    ///     map.insert("/item/:id<u8>", "small")?;
+    ///     map.insert("/item/:name", "name")?;
+    ///
+    ///     assert_eq!(map.capture(b"/item/255"), (Some(&"small"), Params::from(vec![(Bytes::from("id"), "255".as_bytes())])));
+    ///     assert_eq!(map.capture(b"/item/256"), (Some(&"name"), Params::from(vec![(Bytes::from("name"), "256".as_bytes())])));
+    ///
+    ///     // named catch-all captures the rest of the path under its name
+    ///     map.insert("/static/{*file}", "static")?;
+    ///
+    ///     assert_eq!(map.capture(b"/static/css/app.css"), (Some(&"static"), Params::from(vec![(Bytes::from("file"), "css/app.css".as_bytes())])));
+    ///
+    ///     // absent segments report their default values
+    ///     map.insert("/page/:n=1", "page")?;
+    ///     map.insert("/archive[/:year=2024[/:month]]/posts", "archive")?;
+    ///
+    ///     assert_eq!(map.capture(b"/page"), (Some(&"page"), Params::from(vec![(Bytes::from("n"), "1".as_bytes())])));
+    ///     assert_eq!(map.capture(b"/page/5"), (Some(&"page"), Params::from(vec![(Bytes::from("n"), "5".as_bytes())])));
+    ///     assert_eq!(map.capture(b"/archive/posts"), (Some(&"archive"), Params::from(vec![(Bytes::from("year"), "2024".as_bytes())])));
+    ///     assert_eq!(map.capture(b"/archive/2023/posts"), (Some(&"archive"), Params::from(vec![(Bytes::from("year"), "2023".as_bytes())])));
+    ///     assert_eq!(map.capture(b"/archive/2023/05/posts"), (Some(&"archive"), Params::from(vec![(Bytes::from("year"), "2023".as_bytes()), (Bytes::from("month"), "05".as_bytes())])));
+    ///
+    ///     // params stop at the literal following them in the same segment
+    ///     map.insert("/files/:name", "name")?;
+    ///     map.insert("/files/:name.:ext", "file")?;
+    ///     map.insert("/v:major<u8>.:minor<u8>", "version")?;
+    ///     map.insert("/range/:from-:to", "range")?;
+    ///
+    ///     assert_eq!(map.capture(b"/files/readme"), (Some(&"name"), Params::from(vec![(Bytes::from("name"), "readme".as_bytes())])));
+    ///     assert_eq!(map.capture(b"/files/app.tar.gz"), (Some(&"file"), Params::from(vec![(Bytes::from("name"), "app".as_bytes()), (Bytes::from("ext"), "tar.gz".as_bytes())])));
+    ///     assert_eq!(map.capture(b"/v1.2"), (Some(&"version"), Params::from(vec![(Bytes::from("major"), "1".as_bytes()), (Bytes::from("minor"), "2".as_bytes())])));
+    ///     assert_eq!(map.capture(b"/v1.256"), (None, Params::new()));
+    ///     assert_eq!(map.capture(b"/range/10-20"), (Some(&"range"), Params::from(vec![(Bytes::from("from"), "10".as_bytes()), (Bytes::from("to"), "20".as_bytes())])));
+    ///
+    ///     // the name ends at the literal, which may run up to the end of the segment
+    ///     map.insert("/files/:name.json", "json")?;
+    ///     map.insert("/u/:user-id", "user")?;
+    ///
+    ///     assert_eq!(map.capture(b"/files/data.json"), (Some(&"json"), Params::from(vec![(Bytes::from("name"), "data".as_bytes())])));
+    ///     assert_eq!(map.capture(b"/files/app.tar.gz"), (Some(&"file"), Params::from(vec![(Bytes::from("name"), "app".as_bytes()), (Bytes::from("ext"), "tar.gz".as_bytes())])));
+    ///     assert_eq!(map.capture(b"/u/a-b-id"), (Some(&"user"), Params::from(vec![(Bytes::from("user"), "a-b".as_bytes())])));
+    ///
+    ///     Ok(())
+    /// }
+    /// # #[cfg(not(all(feature = "glob", feature = "regex")))]
+    /// # fn main() {}
+    /// ```
+    #[inline]
+    pub fn capture<'u>(&self, path: &'u [u8]) -> (Option<&V>, Params<'u>) {
+        let mut capt = Params::new();
+        let data = self.collect(path, &mut capt);
+        (data, capt)
+    }
+
+    /// Same as capture, but collect the captures into a caller's buffer to avoid allocation.
+    /// The buffer doesn't borrow the path, so it serves paths of any lifetime
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{params::ParamsBuf, RadixMap, RadixResult};
+    ///
+    /// # #[cfg(feature = "glob")]
+    /// fn main() -> RadixResult<()> {
+    ///     let map = RadixMap::try_from([("/user/:id", "user"), ("/blog/:date/*", "blog")])?;
+    ///     let mut buff = ParamsBuf::new();
+    ///
+    ///     let (data, capt) = map.capture_into(b"/user/12345", &mut buff);
+    ///     assert_eq!(data, Some(&"user"));
+    ///     assert_eq!(capt.get("id"), Some(b"12345".as_ref()));
+    ///
+    ///     let path = String::from("/blog/2024-04-10/index.html");
+    ///     let (data, capt) = map.capture_into(path.as_bytes(), &mut buff);
+    ///     assert_eq!(data, Some(&"blog"));
+    ///     assert_eq!(capt.get("date"), Some(b"2024-04-10".as_ref()));
+    ///     assert_eq!(capt.len(), 2);
+    ///
+    ///     let (data, capt) = map.capture_into(b"/api", &mut buff);
+    ///     assert_eq!(data, None);
+    ///     assert!(capt.is_empty());
     ///
     ///     Ok(())
     /// }
+    /// # #[cfg(not(feature = "glob"))]
+    /// # fn main() {}
     /// ```
     #[inline]
-    pub fn capture<'u>(&self, path: &'u [u8]) -> (Option<&V>, Vec<(Bytes, &'u [u8])>) {
-        let mut capt = vec![];
-        let node = self.root.lookup(path, true, false, &mut capt, true);
+    pub fn capture_into<'u>(&self, path: &'u [u8], buffer: &'u mut ParamsBuf) -> (Option<&V>, Params<'u>) {
+        let mut capt = buffer.params(path);
+        let data = self.collect(path, &mut capt);
+        (data, capt)
+    }
+
+    /// Internal use only, find the data and collect the captures, which are cleared on a miss
+    #[inline]
+    fn collect<'u>(&self, path: &'u [u8], capture: &mut Params<'u>) -> Option<&V> {
+        capture.bind(path);
+
+        let node = self.resolve(path, false, capture, true);
         if node.is_none() {
-            capt.clear();
+            capture.clear();
         }
 
-        (node.and_then(|n| n.data.as_ref()), capt)
+        node.and_then(|n| n.data.as_ref())
     }
 
-    /// Retrieve the corresponding mutable data and collect named captures
+    /// Same as capture, but the captures are owned slices of the path which can outlive it
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use radixmap::{params::Params, RadixMap, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let map = RadixMap::try_from([("/user/:id", "user")])?;
+    ///
+    ///     let capt: Params<'static> = {
+    ///         let path = Bytes::from(b"/user/12345".to_vec());
+    ///         map.capture_bytes(path).1
+    ///     };
+    ///
+    ///     assert_eq!(capt.get("id"), Some(b"12345".as_ref()));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn capture_bytes(&self, path: Bytes) -> (Option<&V>, Params<'static>) {
+        let (data, capt) = self.capture(&path);
+        (data, capt.into_owned(&path))
+    }
+
+    /// Iterate all patterns matching the path along with their data and captures.
+    /// Unlike capture which stops at the first hit, every plain, param, regex and glob
+    /// pattern is reported, in the same order as lookup tries them
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use radixmap::{params::Params, RadixMap, RadixResult};
+    ///
+    /// # #[cfg(all(feature = "glob", feature = "regex"))]
+    /// fn main() -> RadixResult<()> {
+    ///     let mut map = RadixMap::new();
+    ///     map.insert("/api/v1/*", "glob")?;
+    ///     map.insert("/api/v1/user/:id", "param")?;
+    ///     map.insert(r"/api/v1/user/{id:\d+}", "regex")?;
+    ///     map.insert("/api/v1/user/12345", "plain")?;
+    ///     map.insert("/api/v2/user/:id", "other")?;
+    ///
+    ///     let mut iter = map.matches(b"/api/v1/user/12345");
+    ///
+    ///     assert_eq!(iter.next(), Some((&Bytes::from("/api/v1/user/12345"), &"plain", Params::new())));
+    ///     assert_eq!(iter.next(), Some((&Bytes::from(r"/api/v1/user/{id:\d+}"), &"regex", Params::from(vec![(Bytes::from("id"), "12345".as_bytes())]))));
+    ///     assert_eq!(iter.next(), Some((&Bytes::from("/api/v1/user/:id"), &"param", Params::from(vec![(Bytes::from("id"), "12345".as_bytes())]))));
+    ///     assert_eq!(iter.next(), Some((&Bytes::from("/api/v1/*"), &"glob", Params::from(vec![(Bytes::from("*"), "user/12345".as_bytes())]))));
+    ///     assert_eq!(iter.next(), None);
+    ///
+    ///     assert_eq!(map.matches(b"/api/v1/user/abc").map(|(_, data, _)| *data).collect::<Vec<_>>(), ["param", "glob"]);
+    ///
+    ///     // the param stopping early at a literal is tried first
+    ///     map.insert("/files/:name", "name")?;
+    ///     map.insert("/files/:name.:ext", "file")?;
+    ///
+    ///     assert_eq!(map.matches(b"/files/app.js").map(|(_, data, _)| *data).collect::<Vec<_>>(), ["file", "name"]);
+    ///     assert_eq!(map.matches(b"/api/v3").count(), 0);
+    ///
+    ///     Ok(())
+    /// }
+    /// # #[cfg(not(all(feature = "glob", feature = "regex")))]
+    /// # fn main() {}
+    /// ```
+    #[inline]
+    pub fn matches<'u>(&self, path: &'u [u8]) -> Matches<'_, 'u, V> {
+        Matches::from((self, path))
+    }
+
+    /// Find the deepest data node whose pattern matches a prefix of the path, and return it
+    /// with the unmatched remainder. Params, regex and glob are honoured along the way
     ///
     /// # Examples
     ///
@@ -250,6 +661,105 @@ impl<V> RadixMap<V> {
     ///
     /// fn main() -> RadixResult<()> {
     ///     let mut map = RadixMap::new();
+    ///     map.insert("/", "root")?;
+    ///     map.insert("/static", "static")?;
+    ///     map.insert("/user/:id", "user")?;
+    ///     map.insert("/files/:name.:ext", "file")?;
+    ///
+    ///     assert_eq!(map.longest_prefix(b"/static/css/app.css"), Some((&Bytes::from("/static"), &"static", b"/css/app.css".as_ref())));
+    ///     assert_eq!(map.longest_prefix(b"/static"), Some((&Bytes::from("/static"), &"static", b"".as_ref())));
+    ///     assert_eq!(map.longest_prefix(b"/stat"), Some((&Bytes::from("/"), &"root", b"stat".as_ref())));
+    ///     assert_eq!(map.longest_prefix(b"/user/12/posts"), Some((&Bytes::from("/user/:id"), &"user", b"/posts".as_ref())));
+    ///     assert_eq!(map.longest_prefix(b"/files/app.json/raw"), Some((&Bytes::from("/files/:name.:ext"), &"file", b"/raw".as_ref())));
+    ///     assert_eq!(map.longest_prefix(b"api"), None);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn longest_prefix<'u>(&self, path: &'u [u8]) -> Option<(&Bytes, &V, &'u [u8])> {
+        let (node, rest) = self.root.lookup_prefix(path)?;
+        let node = self.root.follow(node, &mut Params::new())?;
+        node.item_ref().map(|(path, data)| (path, data, rest))
+    }
+
+    /// Iterate the data nodes whose patterns match a prefix of the path, shortest first, along
+    /// with the unmatched remainder. It walks down the tree once, taking the first child which
+    /// matches at each level in the order of lookup, so the other branches are not visited
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{RadixMap, RadixResult};
+    ///
+    /// # #[cfg(feature = "glob")]
+    /// fn main() -> RadixResult<()> {
+    ///     let mut map = RadixMap::new();
+    ///     map.insert("/*", "fallback")?;
+    ///     map.insert("/api/v1", "v1")?;
+    ///     map.insert("/api", "api")?;
+    ///     map.insert("/", "root")?;
+    ///
+    ///     let mut iter = map.prefixes_of(b"/api/v1/users");
+    ///
+    ///     assert_eq!(iter.next().map(|(_, data, rest)| (*data, rest)), Some(("root", b"api/v1/users".as_ref())));
+    ///     assert_eq!(iter.next().map(|(_, data, rest)| (*data, rest)), Some(("api", b"/v1/users".as_ref())));
+    ///     assert_eq!(iter.next().map(|(_, data, rest)| (*data, rest)), Some(("v1", b"/users".as_ref())));
+    ///     assert_eq!(iter.next(), None);
+    ///
+    ///     // the catch-all is the first child matching the rest
+    ///     assert_eq!(map.prefixes_of(b"/blog/1").map(|(_, data, _)| *data).collect::<Vec<_>>(), ["root", "fallback"]);
+    ///
+    ///     Ok(())
+    /// }
+    /// # #[cfg(not(feature = "glob"))]
+    /// # fn main() {}
+    /// ```
+    #[inline]
+    pub fn prefixes_of<'u>(&self, path: &'u [u8]) -> Prefixes<'_, 'u, V> {
+        Prefixes { root: &self.root, path, next: Prefixes::enter(&self.root, path) }
+    }
+
+    /// Get the data of the path, or the data of its nearest ancestor if there is no exact match.
+    /// The ancestor must end at a separator of the path, either its pattern ends with one or the
+    /// remainder starts with one
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{RadixMap, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let map = RadixMap::try_from([("/", "root"), ("/api", "api"), ("/api/v1", "v1")])?;
+    ///
+    ///     assert_eq!(map.get_inherited(b"/api/v1/users"), Some(&"v1"));
+    ///     assert_eq!(map.get_inherited(b"/api/v1"), Some(&"v1"));
+    ///     assert_eq!(map.get_inherited(b"/api/v2"), Some(&"api"));
+    ///     assert_eq!(map.get_inherited(b"/apix"), Some(&"root"));
+    ///     assert_eq!(map.get_inherited(b"/blog"), Some(&"root"));
+    ///     assert_eq!(map.get_inherited(b"blog"), None);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn get_inherited(&self, path: &[u8]) -> Option<&V> {
+        let sep = self.config.separator.as_ref();
+
+        self.prefixes_of(path).filter(|(_, _, rest)| {
+            rest.is_empty() || rest.starts_with(sep) || path[..path.len() - rest.len()].ends_with(sep)
+        }).last().map(|(_, data, _)| data)
+    }
+
+    /// Retrieve the corresponding mutable data and collect named captures
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use radixmap::{params::Params, RadixMap, RadixResult};
+    ///
+    /// # #[cfg(all(feature = "glob", feature = "regex"))]
+    /// fn main() -> RadixResult<()> {
+    ///     let mut map = RadixMap::new();
     ///     map.insert("/api/v1/user/12345", "user1")?;
     ///     map.insert("/api/v2/user/:id", "user2")?;
     ///     map.insert("/api/v3/user/{id:[0-9]+}", "user3")?;
@@ -258,25 +768,36 @@ impl<V> RadixMap<V> {
     ///     map.insert("/blog/:date/{author:[^/]+}/*.php", "blog")?;
     ///     map.insert("/blog/:date/{author:[^/]+}/:date/*.html", "blog")?;
     ///
-    ///     assert_eq!(map.capture_mut(b"/api/v1/user/12345"), (Some(&mut "user1"), vec![]));
-    ///     assert_eq!(map.capture_mut(b"/api/v2/user/12345"), (Some(&mut "user2"), vec![(Bytes::from("id"), "12345".as_bytes())]));
-    ///     assert_eq!(map.capture_mut(b"/api/v2/user/"), (None, vec![]));
-    ///     assert_eq!(map.capture_mut(b"/api/v3/user/12345"), (Some(&mut "user3"), vec![(Bytes::from("id"), "12345".as_bytes())]));
-    ///     assert_eq!(map.capture_mut(b"/api/v4/user/12345"), (None, vec![]));
-    ///     assert_eq!(map.capture_mut(b"/api/v5/user/12345"), (Some(&mut "user5"), vec![(Bytes::from("*"), "12345".as_bytes())]));
-    ///     assert_eq!(map.capture_mut(b"/api/v5/user/"), (Some(&mut "user5"), vec![(Bytes::from("*"), "".as_bytes())]));
-    ///     assert_eq!(map.capture_mut(b"/api/v6"), (None, vec![]));
-    ///     assert_eq!(map.capture_mut(b"/blog/2024-04-10/chensoft/index.asp"), (None, vec![]));
-    ///     assert_eq!(map.capture_mut(b"/blog/2024-04-10/chensoft/index.php"), (Some(&mut "blog"), vec![(Bytes::from("date"), "2024-04-10".as_bytes()), (Bytes::from("author"), "chensoft".as_bytes()), (Bytes::from("*"), "index.php".as_bytes())]));
-    ///     assert_eq!(map.capture_mut(b"/blog/2024-04-10/chensoft/2024-05-01/index.html"), (Some(&mut "blog"), vec![(Bytes::from("date"), "2024-04-10".as_bytes()), (Bytes::from("author"), "chensoft".as_bytes()), (Bytes::from("date"), "2024-05-01".as_bytes()), (Bytes::from("*"), "index.html".as_bytes())]));
+    ///     assert_eq!(map.capture_mut(b"/api/v1/user/12345"), (Some(&mut "user1"), Params::new()));
+    ///     assert_eq!(map.capture_mut(b"/api/v2/user/12345"), (Some(&mut "user2"), Params::from(vec![(Bytes::from("id"), "12345".as_bytes())])));
+    ///     assert_eq!(map.capture_mut(b"/api/v2/user/"), (None, Params::new()));
+    ///     assert_eq!(map.capture_mut(b"/api/v3/user/12345"), (Some(&mut "user3"), Params::from(vec![(Bytes::from("id"), "12345".as_bytes())])));
+    ///     assert_eq!(map.capture_mut(b"/api/v4/user/12345"), (None, Params::new()));
+    ///     assert_eq!(map.capture_mut(b"/api/v5/user/12345"), (Some(&mut "user5"), Params::from(vec![(Bytes::from("*"), "12345".as_bytes())])));
+    ///     assert_eq!(map.capture_mut(b"/api/v5/user/"), (Some(&mut "user5"), Params::from(vec![(Bytes::from("*"), "".as_bytes())])));
+    ///     assert_eq!(map.capture_mut(b"/api/v6"), (None, Params::new()));
+    ///     assert_eq!(map.capture_mut(b"/blog/2024-04-10/chensoft/index.asp"), (None, Params::new()));
+    ///     assert_eq!(map.capture_mut(b"/blog/2024-04-10/chensoft/index.php"), (Some(&mut "blog"), Params::from(vec![(Bytes::from("date"), "2024-04-10".as_bytes()), (Bytes::from("author"), "chensoft".as_bytes()), (Bytes::from("*"), "index.php".as_bytes())])));
+    ///     assert_eq!(map.capture_mut(b"/blog/2024-04-10/chensoft/2024-05-01/index.html"), (Some(&mut "blog"), Params::from(vec![(Bytes::from("date"), "2024-04-10".as_bytes()), (Bytes::from("author"), "chensoft".as_bytes()), (Bytes::from("date"), "2024-05-01".as_bytes()), (Bytes::from("*"), "index.html".as_bytes())])));
+    ///
+    ///     // fall back to the param if the plain branch dead-ends
+    ///     map.insert("/user/new", "new")?;
+    ///     map.insert("/user/:id", "user")?;
+    ///
+    ///     assert_eq!(map.capture_mut(b"/user/new"), (Some(&mut "new"), Params::new()));
+    ///     assert_eq!(map.capture_mut(b"/user/newer"), (Some(&mut "user"), Params::from(vec![(Bytes::from("id"), "newer".as_bytes())])));
     ///
     ///     Ok(())
     /// }
+    /// # #[cfg(not(all(feature = "glob", feature = "regex")))]
+    /// # fn main() {}
     /// ```
     #[inline]
-    pub fn capture_mut<'u>(&mut self, path: &'u [u8]) -> (Option<&mut V>, Vec<(Bytes, &'u [u8])>) {
-        let mut capt = vec![];
-        let node = self.root.lookup_mut(path, true, false, &mut capt, true);
+    pub fn capture_mut<'u>(&mut self, path: &'u [u8]) -> (Option<&mut V>, Params<'u>) {
+        let mut capt = Params::new();
+        capt.bind(path);
+
+        let node = self.resolve_mut(path, false, &mut capt, true);
         if node.is_none() {
             capt.clear();
         }
@@ -291,6 +812,7 @@ impl<V> RadixMap<V> {
     /// ```
     /// use radixmap::{RadixMap, RadixResult};
     ///
+    /// # #[cfg(all(feature = "glob", feature = "regex"))]
     /// fn main() -> RadixResult<()> {
     ///     let mut map = RadixMap::new();
     ///     map.insert("/api/v1", ())?;
@@ -314,10 +836,12 @@ impl<V> RadixMap<V> {
     ///
     ///     Ok(())
     /// }
+    /// # #[cfg(not(all(feature = "glob", feature = "regex")))]
+    /// # fn main() {}
     /// ```
     #[inline]
     pub fn contains_key(&self, path: &[u8]) -> bool {
-        self.root.lookup(path, true, false, &mut vec![], false).map_or(false, |node| !node.is_empty())
+        self.resolve(path, false, &mut Params::new(), false).is_some_and(|node| !node.is_empty())
     }
 
     /// Check if the tree contains specific data
@@ -374,7 +898,7 @@ impl<V> RadixMap<V> {
     /// }
     /// ```
     #[inline]
-    pub fn iter(&self) -> Iter<V> {
+    pub fn iter(&self) -> Iter<'_, V> {
         Iter::from(self)
     }
 
@@ -407,7 +931,7 @@ impl<V> RadixMap<V> {
     /// }
     /// ```
     #[inline]
-    pub fn iter_mut(&mut self) -> IterMut<V> {
+    pub fn iter_mut(&mut self) -> IterMut<'_, V> {
         IterMut::from(self)
     }
 
@@ -440,7 +964,7 @@ impl<V> RadixMap<V> {
     /// }
     /// ```
     #[inline]
-    pub fn keys(&self) -> Keys<V> {
+    pub fn keys(&self) -> Keys<'_, V> {
         Keys::from(self)
     }
 
@@ -472,7 +996,7 @@ impl<V> RadixMap<V> {
     /// }
     /// ```
     #[inline]
-    pub fn values(&self) -> Values<V> {
+    pub fn values(&self) -> Values<'_, V> {
         Values::from(self)
     }
 
@@ -504,7 +1028,7 @@ impl<V> RadixMap<V> {
     /// }
     /// ```
     #[inline]
-    pub fn values_mut(&mut self) -> ValuesMut<V> {
+    pub fn values_mut(&mut self) -> ValuesMut<'_, V> {
         ValuesMut::from(self)
     }
 
@@ -515,6 +1039,7 @@ impl<V> RadixMap<V> {
     /// ```
     /// use radixmap::{RadixMap, RadixResult};
     ///
+    /// # #[cfg(feature = "glob")]
     /// fn main() -> RadixResult<()> {
     ///     let mut map = RadixMap::new();
     ///
@@ -526,53 +1051,100 @@ impl<V> RadixMap<V> {
     ///     assert_eq!(map.insert("/api/v1", 3)?, Some(1));
     ///     assert_eq!(map.insert("/api/v2", 4)?, Some(2));
     ///
+    ///     // optional segments expand into several routes sharing one value
+    ///     assert_eq!(map.insert("/users/:id?", 5)?, None);
+    ///     assert_eq!(map.get(b"/users"), Some(&5));
+    ///     assert_eq!(map.get(b"/users/12345"), Some(&5));
+    ///     assert_eq!(map.raw(b"/users/:id?"), Some(&5));
+    ///     assert_eq!(map.len(), 4);
+    ///
+    ///     // ambiguous or shadowed special routes are rejected, and the map stays untouched
+    ///     map.insert("/files/*", 6)?;
+    ///
+    ///     assert!(map.insert("/users/:name", 7).is_err());
+    ///     assert!(map.insert("/files/{*rest}", 8).is_err());
+    ///     assert!(map.insert_with_priority("/files/*", 9, 1).is_ok());
+    ///     assert!(map.insert("/files/:name", 10).is_err());
+    ///     assert_eq!(map.len(), 5);
+    ///
+    ///     // the lookup backtracks to the sibling whose continuation differs
+    ///     assert_eq!(map.insert("/users/:name/posts", 11)?, None);
+    ///     assert_eq!(map.get(b"/users/12345"), Some(&5));
+    ///     assert_eq!(map.get(b"/users/chensoft/posts"), Some(&11));
+    ///
     ///     Ok(())
     /// }
+    /// # #[cfg(not(feature = "glob"))]
+    /// # fn main() {}
     /// ```
     #[inline]
     pub fn insert(&mut self, path: impl Into<Bytes>, data: V) -> RadixResult<Option<V>> {
-        let ret = self.root.insert(path, data);
+        let ret = self.root.insert_with(path.into(), data, None, &self.config, &mut self.warnings);
         if let Ok(None) = &ret {
             self.size += 1;
         }
         ret
     }
 
-    /// Remove the nodes along the path, affecting data nodes only
+    /// Insert with an explicit priority which overrides the default order of special nodes.
+    /// Siblings are tried in descending priority, the default priority is 0, and siblings with
+    /// the same priority are tried in the order of plain, regex, param and glob. Plain nodes are
+    /// always tried first no matter what priority they have. Only the last special node of the
+    /// path takes the priority, the nodes shared with other routes keep theirs
     ///
     /// # Examples
     ///
     /// ```
-    /// use bytes::Bytes;
-    /// use radixmap::{RadixMap, RadixResult};
+    /// use radixmap::{RadixMap, RadixError, RadixResult};
     ///
+    /// # #[cfg(all(feature = "glob", feature = "regex"))]
     /// fn main() -> RadixResult<()> {
     ///     let mut map = RadixMap::new();
-    ///     map.insert("/api/v1", "v1")?;
-    ///     map.insert("/api/v2", "v2")?;
-    ///     map.insert("/api", "api")?;
     ///
-    ///     assert_eq!(map.len(), 3);
-    ///     assert_eq!(map.remove(b"/"), None);                          // non-data node
-    ///     assert_eq!(map.remove(b"/api"), Some((Bytes::from("/api"), "api")));      // len - 1
-    ///     assert_eq!(map.remove(b"/api/v2"), Some((Bytes::from("/api/v2"), "v2"))); // len - 1
-    ///     assert_eq!(map.len(), 1);
+    ///     // regex precedes param by default, regardless of the inserting order
+    ///     map.insert("/:id", "param")?;
+    ///     map.insert(r"/{id:\d+}", "regex")?;
+    ///
+    ///     assert_eq!(map.get(b"/123"), Some(&"regex"));
+    ///     assert_eq!(map.get(b"/abc"), Some(&"param"));
+    ///
+    ///     // raise the priority of the param
+    ///     assert_eq!(map.insert_with_priority("/:id", "param", 1)?, Some("param"));
+    ///
+    ///     assert_eq!(map.get(b"/123"), Some(&"param"));
+    ///     assert_eq!(map.get(b"/abc"), Some(&"param"));
+    ///     assert_eq!(map.len(), 2);
+    ///
+    ///     // the priority belongs to the last special node only, the shared prefix keeps its order
+    ///     let mut map = RadixMap::new();
+    ///     map.insert("/:org/:repo", 1)?;
+    ///     map.insert("/{n:[a-z]+}/y", 2)?;
+    ///     map.insert_with_priority("/:org/:repo/*", 3, 5)?;
+    ///
+    ///     assert_eq!(map.get(b"/a/y"), Some(&2));
+    ///     assert_eq!(map.get(b"/a/b/c"), Some(&3));
+    ///     assert_eq!(map.get(b"/a/b"), Some(&1));
+    ///
+    ///     // reordering a node which other routes pass through is a conflict
+    ///     assert!(matches!(map.insert_with_priority("/:org/z", 4, 5), Err(RadixError::Conflict { .. })));
+    ///     assert_eq!(map.get(b"/a/y"), Some(&2));
     ///
     ///     Ok(())
     /// }
+    /// # #[cfg(not(all(feature = "glob", feature = "regex")))]
+    /// # fn main() {}
     /// ```
     #[inline]
-    pub fn remove(&mut self, path: &[u8]) -> Option<(Bytes, V)> {
-        let node = self.root.lookup_mut(path, true, false, &mut vec![], false)?;
-        let path = std::mem::take(&mut node.path);
-        let data = std::mem::take(&mut node.data);
-
-        self.size -= 1;
-
-        Some((path, data?))
+    pub fn insert_with_priority(&mut self, path: impl Into<Bytes>, data: V, priority: i32) -> RadixResult<Option<V>> {
+        let ret = self.root.insert_with(path.into(), data, Some(priority), &self.config, &mut self.warnings);
+        if let Ok(None) = &ret {
+            self.size += 1;
+        }
+        ret
     }
 
-    /// Clear the radix map but preserve its capacity
+    /// Get the entry of the raw path for in-place manipulation. The nodes of the path are
+    /// created in a single walk, and removed again if the vacant entry is dropped unfilled
     ///
     /// # Examples
     ///
@@ -581,33 +1153,416 @@ impl<V> RadixMap<V> {
     ///
     /// fn main() -> RadixResult<()> {
     ///     let mut map = RadixMap::new();
-    ///     map.insert("/api/v1", ())?;
-    ///     map.insert("/api/v2", ())?;
+    ///
+    ///     *map.entry("/api/v1")?.or_insert(0) += 1;
+    ///     *map.entry("/api/v1")?.or_insert(0) += 1;
+    ///     *map.entry("/api/:id")?.or_default() += 10;
     ///
     ///     assert_eq!(map.len(), 2);
+    ///     assert_eq!(map.get(b"/api/v1"), Some(&2));
+    ///     assert_eq!(map.raw(b"/api/:id"), Some(&10));
+    ///     assert!(map.entry("/users/:id?").is_err());
     ///
-    ///     map.clear();
+    ///     // the dropped vacant entry removes the nodes it created
+    ///     map.insert("/x/new", 1)?;
+    ///     let _ = map.entry("/x/:id")?;
     ///
-    ///     assert_eq!(map.is_empty(), true);
-    ///     assert_eq!(map.len(), 0);
+    ///     assert!(map.insert("/x/:name", 2).is_ok());
+    ///     assert_eq!(map.raw(b"/x/:id"), None);
+    ///     assert_eq!(map.len(), 4);
+    ///
+    ///     // the conflicting entry is rejected
+    ///     assert!(map.entry("/x/:id").is_err());
+    ///     assert_eq!(map.len(), 4);
     ///
     ///     Ok(())
     /// }
     /// ```
     #[inline]
-    pub fn clear(&mut self) {
-        self.root.clear();
-        self.size = 0;
-    }
-}
+    pub fn entry(&mut self, path: impl Into<Bytes>) -> RadixResult<Entry<'_, V>> {
+        let path = path.into();
+        if RadixRule::expand(&path, &self.config)?.len() > 1 {
+            return Err(RadixError::PathMalformed("optional segments are not supported in entry"));
+        }
 
-// -----------------------------------------------------------------------------
+        // the node is reached through the pointer, so the release can prune from the root later
+        let mut root = NonNull::from(&mut self.root);
+        let mut pending = vec![];
+        let node = match unsafe { root.as_mut() }.claim(&path, &self.config, &mut pending) {
+            Ok(node) => node,
+            Err(err) => {
+                self.root.prune(&RadixRule::unescape(&path));
+                return Err(err);
+            }
+        };
 
-/// Construct from an array of tuples
-///
-/// # Examples
-///
-/// ```
+        if !node.is_empty() {
+            return Ok(Entry::Occupied(OccupiedEntry { node, size: &mut self.size }));
+        }
+
+        Ok(Entry::Vacant(VacantEntry {
+            node,
+            path: path.clone(),
+            size: &mut self.size,
+            warnings: &mut self.warnings,
+            pending,
+            release: Release { root, path, armed: true },
+        }))
+    }
+
+    /// Remove the data node of the raw path, then prune the empty nodes and merge the plain
+    /// fragments which were divided on insertion
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use radixmap::{RadixMap, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut map = RadixMap::new();
+    ///     map.insert("/api/v1", "v1")?;
+    ///     map.insert("/api/v2", "v2")?;
+    ///     map.insert("/api", "api")?;
+    ///     map.insert("/user/:id", "user")?;
+    ///
+    ///     assert_eq!(map.len(), 4);
+    ///     assert_eq!(map.remove(b"/"), None);                          // non-data node
+    ///     assert_eq!(map.remove(b"/api"), Some((Bytes::from("/api"), "api")));      // len - 1
+    ///     assert_eq!(map.remove(b"/api/v2"), Some((Bytes::from("/api/v2"), "v2"))); // len - 1
+    ///     assert_eq!(map.remove(b"/api/v2"), None);                    // removed already
+    ///     assert_eq!(map.remove(b"/user/123"), None);                  // pattern only
+    ///     assert_eq!(map.remove(b"/user/:id"), Some((Bytes::from("/user/:id"), "user")));
+    ///     assert_eq!(map.len(), 1);
+    ///     assert_eq!(map.get(b"/api/v1"), Some(&"v1"));
+    ///
+    ///     // the whole family of optional segments is removed by the original pattern
+    ///     map.insert("/users[/:id[/:tab]]", "users")?;
+    ///
+    ///     assert_eq!(map.remove(b"/users[/:id[/:tab]]"), Some((Bytes::from("/users[/:id[/:tab]]"), "users")));
+    ///     assert_eq!(map.get(b"/users"), None);
+    ///     assert_eq!(map.get(b"/users/12345"), None);
+    ///     assert_eq!(map.get(b"/users/12345/posts"), None);
+    ///     assert_eq!(map.len(), 1);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn remove(&mut self, path: &[u8]) -> Option<(Bytes, V)> {
+        self.detach(path, true)
+    }
+
+    /// Remove the data node which the concrete path resolves to, like `get` does
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use radixmap::{RadixMap, RadixResult};
+    ///
+    /// # #[cfg(feature = "glob")]
+    /// fn main() -> RadixResult<()> {
+    ///     let mut map = RadixMap::new();
+    ///     map.insert("/user/:id", "user")?;
+    ///     map.insert("/user/*", "glob")?;
+    ///
+    ///     assert_eq!(map.remove_matching(b"/user/123"), Some((Bytes::from("/user/:id"), "user")));
+    ///     assert_eq!(map.remove_matching(b"/user/123"), Some((Bytes::from("/user/*"), "glob")));
+    ///     assert_eq!(map.remove_matching(b"/user/123"), None);
+    ///     assert_eq!(map.len(), 0);
+    ///
+    ///     Ok(())
+    /// }
+    /// # #[cfg(not(feature = "glob"))]
+    /// # fn main() {}
+    /// ```
+    #[inline]
+    pub fn remove_matching(&mut self, path: &[u8]) -> Option<(Bytes, V)> {
+        self.detach(path, false)
+    }
+
+    /// Remove every data node whose pattern matches the concrete path
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use radixmap::{RadixMap, RadixResult};
+    ///
+    /// # #[cfg(feature = "glob")]
+    /// fn main() -> RadixResult<()> {
+    ///     let mut map = RadixMap::new();
+    ///     map.insert("/user/:id", "user")?;
+    ///     map.insert("/user/*", "glob")?;
+    ///     map.insert("/user/new", "new")?;
+    ///
+    ///     assert_eq!(map.remove_all_matching(b"/user/123"), vec![(Bytes::from("/user/:id"), "user"), (Bytes::from("/user/*"), "glob")]);
+    ///     assert_eq!(map.remove_all_matching(b"/user/123"), vec![]);
+    ///     assert_eq!(map.len(), 1);
+    ///
+    ///     Ok(())
+    /// }
+    /// # #[cfg(not(feature = "glob"))]
+    /// # fn main() {}
+    /// ```
+    #[inline]
+    pub fn remove_all_matching(&mut self, path: &[u8]) -> Vec<(Bytes, V)> {
+        let mut list = vec![];
+
+        while let Some(item) = self.remove_matching(path) {
+            list.push(item);
+        }
+
+        list
+    }
+
+    /// Internal use only, take the data out of the found node and re-balance the tree
+    fn detach(&mut self, path: &[u8], raw: bool) -> Option<(Bytes, V)> {
+        let node = self.resolve_mut(path, raw, &mut Params::new(), false)?;
+        let data = node.data.take()?;
+        let path = core::mem::take(&mut node.path);
+
+        self.size -= 1;
+
+        // the aliases of the optional segments go along with the data node
+        let routes = match RadixRule::expand(&path, &self.config) {
+            Ok(routes) => routes,
+            Err(_) => vec![(path.clone(), vec![])],
+        };
+
+        let link = &routes[0].0;
+
+        for (route, _) in routes.iter().skip(1) {
+            if let Some(node) = self.root.lookup_mut(&RadixRule::unescape(route), true, true, &mut Params::new(), false) {
+                if node.alias.as_ref().is_some_and(|alias| alias.link == *link) {
+                    node.alias = None;
+                }
+            }
+        }
+
+        for (route, _) in routes.iter() {
+            self.root.prune(&RadixRule::unescape(route));
+        }
+
+        Some((path, data))
+    }
+
+    /// Internal use only, find the node of the path and follow the alias to its data node.
+    /// The track records the steps from the root to the data node
+    fn find<'n, 'u>(&'n self, path: &'u [u8], raw: bool, capture: &mut Params<'u>, enable: bool, mut track: Option<&mut Track<&'n RadixNode<V>>>) -> Option<&'n RadixNode<V>> {
+        // the raw pattern is compared in its canonical form, which has no escapes
+        let found = match raw {
+            true => self.root.trace(&RadixRule::unescape(path), true, true, &mut Params::new(), false, track.as_deref_mut()),
+            false => self.root.trace(path, true, false, capture, enable, track.as_deref_mut()),
+        };
+
+        let node = match found {
+            Some(node) => node,
+            None if raw => {
+                // a raw pattern with optional segments is addressed by its full route
+                let mut routes = RadixRule::expand(&Bytes::copy_from_slice(path), &self.config).ok().filter(|routes| routes.len() > 1)?;
+                let (route, _) = routes.swap_remove(0);
+                self.root.trace(&RadixRule::unescape(&route), true, true, &mut Params::new(), false, track.as_deref_mut())?
+            }
+            None => return None,
+        };
+
+        RadixWalk::follow(node, &self.root, capture, track)
+    }
+
+    /// Internal use only, find the data node of the path
+    #[inline]
+    fn resolve<'u>(&self, path: &'u [u8], raw: bool, capture: &mut Params<'u>, enable: bool) -> Option<&RadixNode<V>> {
+        self.find(path, raw, capture, enable, None)
+    }
+
+    /// Internal use only, find the mutable data node of the path. The walk is immutable since
+    /// the alias can't be followed on a mutable borrow, then its steps lead to the mutable node
+    fn resolve_mut<'u>(&mut self, path: &'u [u8], raw: bool, capture: &mut Params<'u>, enable: bool) -> Option<&mut RadixNode<V>> {
+        let mut track = Track::trail();
+        self.find(path, raw, capture, enable, Some(&mut track))?;
+        self.root.descend(&track.steps)
+    }
+
+    /// Remove the empty nodes left by entries and merge the divided plain fragments
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{RadixMap, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut map = RadixMap::new();
+    ///     map.insert("/api/v1", "v1")?;
+    ///     map.entry("/api/v2/:id")?; // vacant entry creates empty nodes
+    ///
+    ///     map.compact();
+    ///
+    ///     assert_eq!(map.len(), 1);
+    ///     assert_eq!(map.get(b"/api/v1"), Some(&"v1"));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn compact(&mut self) {
+        self.root.compact();
+    }
+
+    /// Compact the tree and release the unused capacity
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{RadixMap, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut map = RadixMap::new();
+    ///     map.insert("/api/v1", "v1")?;
+    ///     map.insert("/api/v2", "v2")?;
+    ///     map.remove(b"/api/v2");
+    ///
+    ///     map.shrink_to_fit();
+    ///
+    ///     assert_eq!(map.len(), 1);
+    ///     assert_eq!(map.get(b"/api/v1"), Some(&"v1"));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn shrink_to_fit(&mut self) {
+        self.root.shrink_to_fit();
+    }
+
+    /// Clear the radix map but preserve its capacity
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{RadixMap, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut map = RadixMap::new();
+    ///     map.insert("/api/v1", ())?;
+    ///     map.insert("/api/v2", ())?;
+    ///
+    ///     assert_eq!(map.len(), 2);
+    ///
+    ///     map.clear();
+    ///
+    ///     assert_eq!(map.is_empty(), true);
+    ///     assert_eq!(map.len(), 0);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn clear(&mut self) {
+        self.root.clear();
+        self.size = 0;
+        self.warnings.clear();
+    }
+
+    /// Persist the whole tree, including the node layout and the config except the matchers,
+    /// the data is encoded by RadixCodec
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{RadixMap, RadixError, RadixResult};
+    ///
+    /// # #[cfg(feature = "glob")]
+    /// fn main() -> RadixResult<()> {
+    ///     let map = RadixMap::try_from([("/api/v1", 1u32), ("/api/:id", 2), ("/static/*", 3)])?;
+    ///
+    ///     let mut snapshot = vec![];
+    ///     map.write_snapshot(&mut snapshot)?;
+    ///
+    ///     let load = RadixMap::<u32>::read_snapshot(snapshot.as_slice())?;
+    ///
+    ///     assert_eq!(load, map);
+    ///     assert_eq!(load.get(b"/api/12345"), Some(&2));
+    ///     assert_eq!(load.get(b"/static/css/app.css"), Some(&3));
+    ///
+    ///     // truncated or corrupt snapshots are rejected
+    ///     assert!(matches!(RadixMap::<u32>::read_snapshot(&snapshot[..snapshot.len() - 1]), Err(RadixError::SnapshotInvalid(_))));
+    ///
+    ///     snapshot[20] ^= 0xff;
+    ///     assert!(matches!(RadixMap::<u32>::read_snapshot(snapshot.as_slice()), Err(RadixError::SnapshotInvalid(_))));
+    ///
+    ///     Ok(())
+    /// }
+    /// # #[cfg(not(feature = "glob"))]
+    /// # fn main() {}
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn write_snapshot(&self, writer: &mut impl std::io::Write) -> RadixResult<()> where V: RadixCodec {
+        Ok(writer.write_all(&snapshot::encode(&self.root, self.size, &self.config))?)
+    }
+
+    /// Load the tree persisted by write_snapshot, the paths are not split again and only the
+    /// glob and regex rules are compiled
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn read_snapshot(reader: impl std::io::Read) -> RadixResult<Self> where V: RadixCodec {
+        Self::read_snapshot_with_config(reader, RadixConfig::default())
+    }
+
+    /// Same as read_snapshot, the custom matchers are taken from the config, while the separator
+    /// and the flags are restored from the snapshot
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use radixmap::{RadixMap, RadixResult, rule::RadixConfig};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let digits = |path: &[u8]| -> Option<(usize, Option<Bytes>)> {
+    ///         let len = path.iter().take_while(|c| c.is_ascii_digit()).count();
+    ///         (len > 0).then_some((len, None))
+    ///     };
+    ///
+    ///     let mut map = RadixMap::with_separator("::")?;
+    ///     map.register("digits", digits);
+    ///     map.insert("crate::{id:@digits}", true)?;
+    ///
+    ///     let mut snapshot = vec![];
+    ///     map.write_snapshot(&mut snapshot)?;
+    ///
+    ///     // the matcher isn't persisted
+    ///     assert!(RadixMap::<bool>::read_snapshot(snapshot.as_slice()).is_err());
+    ///
+    ///     let mut config = RadixConfig::default();
+    ///     config.register("digits", digits);
+    ///
+    ///     let load = RadixMap::<bool>::read_snapshot_with_config(snapshot.as_slice(), config)?;
+    ///
+    ///     assert_eq!(load.get(b"crate::12345"), Some(&true));
+    ///     assert_eq!(load.get(b"crate::abc"), None);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn read_snapshot_with_config(mut reader: impl std::io::Read, config: RadixConfig) -> RadixResult<Self> where V: RadixCodec {
+        let mut data = vec![];
+        reader.read_to_end(&mut data)?;
+
+        let (root, size, config) = snapshot::decode(&data, config)?;
+        Ok(Self { root, size, config, warnings: vec![] })
+    }
+}
+
+// -----------------------------------------------------------------------------
+
+/// Construct from an array of tuples
+///
+/// # Examples
+///
+/// ```
 /// use radixmap::{RadixMap, RadixResult};
 ///
 /// fn main() -> RadixResult<()> {
@@ -660,7 +1615,7 @@ impl<V, const N: usize> TryFrom<[(&'static str, V); N]> for RadixMap<V> {
 impl<V> Default for RadixMap<V> {
     #[inline]
     fn default() -> Self {
-        Self { root: RadixNode::default(), size: 0 }
+        Self { root: RadixNode::default(), size: 0, config: RadixConfig::default(), warnings: vec![] }
     }
 }
 
@@ -683,7 +1638,7 @@ impl<V> Default for RadixMap<V> {
 impl<V: Clone> Clone for RadixMap<V> {
     #[inline]
     fn clone(&self) -> Self {
-        Self { root: self.root.clone(), size: self.size }
+        Self { root: self.root.clone(), size: self.size, config: self.config.clone(), warnings: self.warnings.clone() }
     }
 }
 
@@ -704,7 +1659,7 @@ impl<V: Clone> Clone for RadixMap<V> {
 /// ```
 impl<V: Debug> Debug for RadixMap<V> {
     #[inline]
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         f.debug_map().entries(self.iter()).finish()
     }
 }
@@ -791,6 +1746,437 @@ impl<V> IndexMut<&[u8]> for RadixMap<V> {
     }
 }
 
+/// Serialize as a map from the raw pattern to the data, in the iterating order. The config isn't
+/// serialized, restore it via RadixMap::seed
+///
+/// # Examples
+///
+/// ```
+/// use radixmap::{RadixMap, RadixResult};
+///
+/// fn main() -> RadixResult<()> {
+///     let map = RadixMap::try_from([("/api/v1", 1), ("/api/:id", 2), ("/api", 0)])?;
+///
+///     assert_eq!(serde_json::to_string(&map).ok(), Some(r#"{"/api":0,"/api/v1":1,"/api/:id":2}"#.to_string()));
+///
+///     Ok(())
+/// }
+/// ```
+#[cfg(feature = "serde")]
+impl<V: serde::Serialize> serde::Serialize for RadixMap<V> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        let mut map = serializer.serialize_map(Some(self.len()))?;
+
+        for (path, data) in self.iter() {
+            map.serialize_entry(&Pattern(path.clone()), data)?;
+        }
+
+        map.end()
+    }
+}
+
+/// Deserialize from a map of the raw pattern to the data, the tree is rebuilt with the default
+/// config, see RadixMap::seed for another one, and the malformed pattern is reported along with
+/// its key
+///
+/// # Examples
+///
+/// ```
+/// use radixmap::RadixMap;
+///
+/// let map: RadixMap<u32> = serde_json::from_str(r#"{"/api":0,"/api/v1":1,"/api/:id":2}"#).unwrap();
+///
+/// assert_eq!(map.len(), 3);
+/// assert_eq!(map.get(b"/api/v1"), Some(&1));
+/// assert_eq!(map.get(b"/api/12345"), Some(&2));
+///
+/// let err = serde_json::from_str::<RadixMap<u32>>(r#"{"/api":0,"/api/{id":1}"#).unwrap_err();
+/// assert!(err.to_string().starts_with("invalid pattern '/api/{id': missing closing sign '}'"));
+/// ```
+#[cfg(feature = "serde")]
+impl<'de, V: serde::Deserialize<'de>> serde::Deserialize<'de> for RadixMap<V> {
+    #[inline]
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        serde::de::DeserializeSeed::deserialize(Self::seed(RadixConfig::default()), deserializer)
+    }
+}
+
+/// The seed which deserializes a map with the given config, see RadixMap::seed
+#[cfg(feature = "serde")]
+pub struct Seed<V> {
+    /// The options of the rules
+    config: RadixConfig,
+
+    /// The data type
+    kind: core::marker::PhantomData<fn() -> V>,
+}
+
+#[cfg(feature = "serde")]
+impl<'de, V: serde::Deserialize<'de>> serde::de::DeserializeSeed<'de> for Seed<V> {
+    type Value = RadixMap<V>;
+
+    fn deserialize<D: serde::Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        struct Visitor<V>(RadixConfig, core::marker::PhantomData<V>);
+
+        impl<'de, V: serde::Deserialize<'de>> serde::de::Visitor<'de> for Visitor<V> {
+            type Value = RadixMap<V>;
+
+            fn expecting(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+                f.write_str("a map of patterns")
+            }
+
+            fn visit_map<A: serde::de::MapAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+                let mut map = RadixMap::with_config(self.0);
+
+                while let Some((Pattern(path), data)) = access.next_entry()? {
+                    map.insert(path.clone(), data).map_err(|err| Pattern::error(&path, err))?;
+                }
+
+                Ok(map)
+            }
+        }
+
+        deserializer.deserialize_map(Visitor(self.config, core::marker::PhantomData))
+    }
+}
+
+// -----------------------------------------------------------------------------
+
+/// A view into a single node of the map, which may either be vacant or occupied
+pub enum Entry<'n, V> {
+    /// The node holds data already
+    Occupied(OccupiedEntry<'n, V>),
+
+    /// The node is empty
+    Vacant(VacantEntry<'n, V>),
+}
+
+impl<'n, V> Entry<'n, V> {
+    /// The raw path of the entry
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use radixmap::{RadixMap, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut map = RadixMap::<()>::new();
+    ///
+    ///     assert_eq!(map.entry("/api/:id")?.key(), &Bytes::from("/api/:id"));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn key(&self) -> &Bytes {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Ensure a value is in the entry by inserting the default if empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{RadixMap, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut map = RadixMap::new();
+    ///
+    ///     assert_eq!(map.entry("/api")?.or_insert(1), &mut 1);
+    ///     assert_eq!(map.entry("/api")?.or_insert(2), &mut 1);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn or_insert(self, data: V) -> &'n mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(data),
+        }
+    }
+
+    /// Ensure a value is in the entry by inserting the result of the function if empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{RadixMap, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut map = RadixMap::new();
+    ///
+    ///     assert_eq!(map.entry("/api")?.or_insert_with(|| 1), &mut 1);
+    ///     assert_eq!(map.entry("/api")?.or_insert_with(|| unreachable!()), &mut 1);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn or_insert_with<F: FnOnce() -> V>(self, func: F) -> &'n mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(func()),
+        }
+    }
+
+    /// Ensure a value is in the entry by inserting the default value if empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{RadixMap, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut map = RadixMap::<usize>::new();
+    ///
+    ///     assert_eq!(map.entry("/api")?.or_default(), &mut 0);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn or_default(self) -> &'n mut V where V: Default {
+        self.or_insert_with(Default::default)
+    }
+
+    /// Provide in-place mutable access to an occupied entry
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{RadixMap, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut map = RadixMap::new();
+    ///
+    ///     assert_eq!(map.entry("/api")?.and_modify(|data| *data += 1).or_insert(1), &mut 1);
+    ///     assert_eq!(map.entry("/api")?.and_modify(|data| *data += 1).or_insert(1), &mut 2);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn and_modify<F: FnOnce(&mut V)>(mut self, func: F) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            func(entry.get_mut());
+        }
+
+        self
+    }
+
+    /// Internal use only, insert the data if empty and return the path-data pair
+    #[inline]
+    pub(crate) fn or_insert_item(self, data: V) -> (&'n Bytes, &'n mut V) {
+        let node = match self {
+            Entry::Occupied(entry) => entry.node,
+            Entry::Vacant(entry) => {
+                let node = entry.occupy();
+                node.data = Some(data);
+                node
+            }
+        };
+
+        match node.item_mut() {
+            Some(item) => item,
+            None => unreachable!()
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+
+/// A view into an occupied node
+pub struct OccupiedEntry<'n, V> {
+    node: &'n mut RadixNode<V>,
+    size: &'n mut usize,
+}
+
+impl<'n, V> OccupiedEntry<'n, V> {
+    /// The raw path of the entry
+    #[inline]
+    pub fn key(&self) -> &Bytes {
+        &self.node.path
+    }
+
+    /// Get a reference to the data
+    #[inline]
+    pub fn get(&self) -> &V {
+        match &self.node.data {
+            Some(data) => data,
+            None => unreachable!()
+        }
+    }
+
+    /// Get a mutable reference to the data
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut V {
+        match &mut self.node.data {
+            Some(data) => data,
+            None => unreachable!()
+        }
+    }
+
+    /// Convert the entry into a mutable reference bound to the map
+    #[inline]
+    pub fn into_mut(self) -> &'n mut V {
+        match &mut self.node.data {
+            Some(data) => data,
+            None => unreachable!()
+        }
+    }
+
+    /// Replace the data and return the old one
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{map::Entry, RadixMap, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut map = RadixMap::try_from([("/api", 1)])?;
+    ///
+    ///     if let Entry::Occupied(mut entry) = map.entry("/api")? {
+    ///         assert_eq!(entry.insert(2), 1);
+    ///     }
+    ///
+    ///     assert_eq!(map.get(b"/api"), Some(&2));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn insert(&mut self, data: V) -> V {
+        core::mem::replace(self.get_mut(), data)
+    }
+
+    /// Take the data out of the map
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{map::Entry, RadixMap, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut map = RadixMap::try_from([("/api", 1)])?;
+    ///
+    ///     if let Entry::Occupied(entry) = map.entry("/api")? {
+    ///         assert_eq!(entry.remove(), 1);
+    ///     }
+    ///
+    ///     assert_eq!(map.len(), 0);
+    ///     assert_eq!(map.get(b"/api"), None);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+
+    /// Take the path-data pair out of the map, the emptied node is kept until `compact`
+    #[inline]
+    pub fn remove_entry(self) -> (Bytes, V) {
+        let path = core::mem::take(&mut self.node.path);
+        let data = match self.node.data.take() {
+            Some(data) => data,
+            None => unreachable!()
+        };
+
+        *self.size -= 1;
+
+        (path, data)
+    }
+}
+
+// -----------------------------------------------------------------------------
+
+/// A view into a vacant node
+pub struct VacantEntry<'n, V> {
+    node: &'n mut RadixNode<V>,
+    path: Bytes,
+    size: &'n mut usize,
+    warnings: &'n mut Vec<RadixError>,
+    pending: Vec<RadixError>,
+    release: Release<V>,
+}
+
+impl<'n, V> VacantEntry<'n, V> {
+    /// The raw path of the entry
+    #[inline]
+    pub fn key(&self) -> &Bytes {
+        &self.path
+    }
+
+    /// Take ownership of the raw path
+    #[inline]
+    pub fn into_key(self) -> Bytes {
+        self.path
+    }
+
+    /// Set the data of the entry and return a mutable reference to it
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{map::Entry, RadixMap, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut map = RadixMap::new();
+    ///
+    ///     if let Entry::Vacant(entry) = map.entry("/api")? {
+    ///         assert_eq!(entry.insert(1), &mut 1);
+    ///     }
+    ///
+    ///     assert_eq!(map.len(), 1);
+    ///     assert_eq!(map.get(b"/api"), Some(&1));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn insert(self, data: V) -> &'n mut V {
+        self.occupy().data.insert(data)
+    }
+
+    /// Internal use only, keep the node for the data and record the warnings found by entry
+    fn occupy(mut self) -> &'n mut RadixNode<V> {
+        self.release.armed = false;
+        self.warnings.append(&mut self.pending);
+        *self.size += 1;
+
+        self.node.path = self.path;
+        self.node
+    }
+}
+
+/// Internal use only, remove the nodes created for the vacant entry if it's dropped unfilled
+struct Release<V> {
+    root: NonNull<RadixNode<V>>,
+    path: Bytes,
+    armed: bool,
+}
+
+// the pointer stands for the mutable borrow of the root held by the entry
+unsafe impl<V: Send> Send for Release<V> {}
+unsafe impl<V: Sync> Sync for Release<V> {}
+
+impl<V> Drop for Release<V> {
+    fn drop(&mut self) {
+        // the node of the entry is no longer used, so the tree is borrowed by this only
+        if self.armed {
+            unsafe { self.root.as_mut() }.prune(&RadixRule::unescape(&self.path));
+        }
+    }
+}
+
 // -----------------------------------------------------------------------------
 
 /// Re-import Order
@@ -798,10 +2184,70 @@ pub type Order = node::Order;
 
 // -----------------------------------------------------------------------------
 
+/// The raw pattern as a serde key, a string in human-readable formats and bytes in the others
+#[cfg(feature = "serde")]
+pub(crate) struct Pattern(pub(crate) Bytes);
+
+#[cfg(feature = "serde")]
+impl Pattern {
+    /// The error of the malformed pattern, naming the pattern
+    pub(crate) fn error<E: serde::de::Error>(path: &Bytes, err: RadixError) -> E {
+        E::custom(format_args!("invalid pattern '{}': {}", String::from_utf8_lossy(path), err))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Pattern {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match serializer.is_human_readable() {
+            true => serializer.serialize_str(core::str::from_utf8(&self.0).map_err(serde::ser::Error::custom)?),
+            false => serializer.serialize_bytes(&self.0),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Pattern {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl serde::de::Visitor<'_> for Visitor {
+            type Value = Pattern;
+
+            fn expecting(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+                f.write_str("a pattern")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                Ok(Pattern(Bytes::copy_from_slice(value.as_bytes())))
+            }
+
+            fn visit_string<E: serde::de::Error>(self, value: String) -> Result<Self::Value, E> {
+                Ok(Pattern(Bytes::from(value)))
+            }
+
+            fn visit_bytes<E: serde::de::Error>(self, value: &[u8]) -> Result<Self::Value, E> {
+                Ok(Pattern(Bytes::copy_from_slice(value)))
+            }
+
+            fn visit_byte_buf<E: serde::de::Error>(self, value: Vec<u8>) -> Result<Self::Value, E> {
+                Ok(Pattern(Bytes::from(value)))
+            }
+        }
+
+        match deserializer.is_human_readable() {
+            true => deserializer.deserialize_str(Visitor),
+            false => deserializer.deserialize_bytes(Visitor),
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+
 /// Iterator for map
 #[derive(Default, Clone)]
 pub struct Iter<'n, V> {
-    iter: node::Iter<'n, V>
+    pub(crate) iter: node::Iter<'n, V>
 }
 
 impl<'n, V> Iter<'n, V> {
@@ -841,7 +2287,7 @@ impl<'n, V> Iterator for Iter<'n, V> {
 /// Mutable iterator for map
 #[derive(Default)]
 pub struct IterMut<'n, V> {
-    iter: node::IterMut<'n, V>
+    pub(crate) iter: node::IterMut<'n, V>
 }
 
 impl<'n, V> IterMut<'n, V> {
@@ -881,7 +2327,7 @@ impl<'n, V> Iterator for IterMut<'n, V> {
 /// Path adapter
 #[derive(Default, Clone)]
 pub struct Keys<'n, V> {
-    iter: Iter<'n, V>
+    pub(crate) iter: Iter<'n, V>
 }
 
 impl<'n, V> Keys<'n, V> {
@@ -921,7 +2367,7 @@ impl<'n, V> Iterator for Keys<'n, V> {
 /// Data adapter
 #[derive(Default, Clone)]
 pub struct Values<'n, V> {
-    iter: Iter<'n, V>
+    pub(crate) iter: Iter<'n, V>
 }
 
 impl<'n, V> Values<'n, V> {
@@ -961,7 +2407,7 @@ impl<'n, V> Iterator for Values<'n, V> {
 /// Mutable data adapter
 #[derive(Default)]
 pub struct ValuesMut<'n, V> {
-    iter: IterMut<'n, V>
+    pub(crate) iter: IterMut<'n, V>
 }
 
 impl<'n, V> ValuesMut<'n, V> {
@@ -994,4 +2440,84 @@ impl<'n, V> Iterator for ValuesMut<'n, V> {
     fn next(&mut self) -> Option<Self::Item> {
         self.iter.next().map(|item| item.1)
     }
-}
\ No newline at end of file
+}
+
+// -----------------------------------------------------------------------------
+
+/// Iterator for all patterns matching a path
+#[derive(Clone)]
+pub struct Matches<'n, 'u, V> {
+    root: &'n RadixNode<V>,
+    iter: node::Matches<'n, 'u, V>
+}
+
+impl<'n, 'u, V> From<(&'n RadixMap<V>, &'u [u8])> for Matches<'n, 'u, V> {
+    #[inline]
+    fn from((value, path): (&'n RadixMap<V>, &'u [u8])) -> Self {
+        Self { root: &value.root, iter: value.root.matches(path) }
+    }
+}
+
+impl<'n, 'u, V> Iterator for Matches<'n, 'u, V> {
+    type Item = (&'n Bytes, &'n V, Params<'u>);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        for (node, mut capt) in self.iter.by_ref() {
+            if let Some((path, data)) = self.root.follow(node, &mut capt).and_then(|node| node.item_ref()) {
+                return Some((path, data, capt));
+            }
+        }
+
+        None
+    }
+}
+
+// -----------------------------------------------------------------------------
+
+/// Iterator for the patterns matching a prefix of a path, shortest first
+#[derive(Clone)]
+pub struct Prefixes<'n, 'u, V> {
+    root: &'n RadixNode<V>,
+    path: &'u [u8],
+    next: Option<(&'n RadixNode<V>, &'u [u8])>,
+}
+
+impl<'n, 'u, V> Prefixes<'n, 'u, V> {
+    /// Internal use only, the part of the path taken by the node if its fragment is consumed
+    /// entirely, the param stops at the first literal following it
+    fn enter(node: &'n RadixNode<V>, path: &'u [u8]) -> Option<(&'n RadixNode<V>, &'u [u8])> {
+        if let Some(pos) = node.cuts(path).next() {
+            return Some((node, &path[..pos]));
+        }
+
+        let share = node.rule.longest(path, false)?;
+        match node.rule.is_special() || node.rule.origin().len() == share.len() {
+            true => Some((node, share)),
+            false => None,
+        }
+    }
+}
+
+impl<'n, 'u, V> Iterator for Prefixes<'n, 'u, V> {
+    type Item = (&'n Bytes, &'n V, &'u [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((node, share)) = self.next.take() {
+            let rest = &self.path[share.len()..];
+
+            // go down to the first child matching the rest, in the order of lookup
+            self.path = rest;
+            self.next = rest.first().and_then(|&byte| {
+                let found = node.next.regular.get(node.next.index(byte));
+                found.into_iter().chain(node.next.special.values()).find_map(|next| Self::enter(next, rest))
+            });
+
+            if let Some((path, data)) = self.root.follow(node, &mut Params::new()).and_then(|node| node.item_ref()) {
+                return Some((path, data, rest));
+            }
+        }
+
+        None
+    }
+}
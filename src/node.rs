@@ -2,6 +2,7 @@
 use super::pack;
 use super::defs::*;
 use super::rule::*;
+use super::params::Params;
 
 /// The basic element inside a tree
 pub struct RadixNode<V> {
@@ -14,10 +15,26 @@ pub struct RadixNode<V> {
     /// The pattern used for matching, supports plain text, named param, glob and regex
     pub rule: RadixRule,
 
+    /// The order among special siblings, higher is tried first
+    pub priority: i32,
+
+    /// The data node which this node stands for, valid in alias-node only
+    pub alias: Option<Box<RadixAlias>>,
+
     /// Node's children
     pub next: pack::RadixPack<V>,
 }
 
+/// The alias of a data node, created by expanding the optional segments
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RadixAlias {
+    /// The concrete route of the data node
+    pub link: Bytes,
+
+    /// The captures reported for the absent params
+    pub defaults: Defaults,
+}
+
 impl<V> RadixNode<V> {
     /// Check if the node has no data
     #[inline]
@@ -25,6 +42,12 @@ impl<V> RadixNode<V> {
         self.data.is_none()
     }
 
+    /// Check if the node only stands for another data node
+    #[inline]
+    pub fn is_alias(&self) -> bool {
+        self.data.is_none() && self.alias.is_some()
+    }
+
     /// Get path-data pair
     #[inline]
     pub fn item_ref(&self) -> Option<(&Bytes, &V)> {
@@ -62,7 +85,7 @@ impl<V> RadixNode<V> {
     /// }
     /// ```
     #[inline]
-    pub fn iter(&self) -> Iter<V> {
+    pub fn iter(&self) -> Iter<'_, V> {
         Iter::from(self)
     }
 
@@ -95,7 +118,7 @@ impl<V> RadixNode<V> {
     /// }
     /// ```
     #[inline]
-    pub fn iter_mut(&mut self) -> IterMut<V> {
+    pub fn iter_mut(&mut self) -> IterMut<'_, V> {
         IterMut::from(self)
     }
 
@@ -124,7 +147,7 @@ impl<V> RadixNode<V> {
     /// }
     /// ```
     #[inline]
-    pub fn keys(&self) -> Keys<V> {
+    pub fn keys(&self) -> Keys<'_, V> {
         Keys::from(self)
     }
 
@@ -152,7 +175,7 @@ impl<V> RadixNode<V> {
     /// }
     /// ```
     #[inline]
-    pub fn values(&self) -> Values<V> {
+    pub fn values(&self) -> Values<'_, V> {
         Values::from(self)
     }
 
@@ -184,33 +207,245 @@ impl<V> RadixNode<V> {
     /// }
     /// ```
     #[inline]
-    pub fn values_mut(&mut self) -> ValuesMut<V> {
+    pub fn values_mut(&mut self) -> ValuesMut<'_, V> {
         ValuesMut::from(self)
     }
 
     /// Inserts a path and data into this node, which serves as the root node for the insertion.
     /// The method sequentially extracts path fragments and positions each node appropriately,
     /// ensuring that nodes with a common prefix share a single node in the tree.
+    ///
+    /// The optional segments and default values are expanded into several routes, the one with
+    /// all segments holds the data, and the others are aliases of it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{node::RadixNode, params::Params, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut node = RadixNode::default();
+    ///     node.insert("/users/:id?", "users")?;
+    ///
+    ///     let main = node.lookup(b"/users/1", true, false, &mut Params::new(), false);
+    ///     let alias = node.lookup(b"/users", true, false, &mut Params::new(), false);
+    ///
+    ///     assert_eq!(main.and_then(|node| node.data), Some("users"));
+    ///     assert_eq!(alias.and_then(|node| node.alias.as_ref()).map(|alias| alias.link.as_ref()), Some(b"/users/:id".as_ref()));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
     pub fn insert(&mut self, path: impl Into<Bytes>, data: V) -> RadixResult<Option<V>> {
-        let path = path.into();
-        let mut frag = path.clone();
+        self.insert_with(path.into(), data, None, &RadixConfig::default(), &mut vec![])
+    }
+
+    /// Same as insert, but assign an explicit priority to the last special node of the path
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{node::RadixNode, params::Params, RadixResult};
+    ///
+    /// # #[cfg(feature = "regex")]
+    /// fn main() -> RadixResult<()> {
+    ///     let mut node = RadixNode::default();
+    ///     node.insert(r"/{id:\d+}", "regex")?;
+    ///     node.insert_with_priority("/:id", "param", 1)?;
+    ///
+    ///     assert_eq!(node.lookup(b"/123", true, false, &mut Params::new(), false).and_then(|node| node.data), Some("param"));
+    ///
+    ///     Ok(())
+    /// }
+    /// # #[cfg(not(feature = "regex"))]
+    /// # fn main() {}
+    /// ```
+    #[inline]
+    pub fn insert_with_priority(&mut self, path: impl Into<Bytes>, data: V, priority: i32) -> RadixResult<Option<V>> {
+        self.insert_with(path.into(), data, Some(priority), &RadixConfig::default(), &mut vec![])
+    }
+
+    /// Internal use only, insert the expanded routes and link the aliases to the data node
+    pub(crate) fn insert_with(&mut self, path: Bytes, data: V, priority: Option<i32>, config: &RadixConfig, warnings: &mut Vec<RadixError>) -> RadixResult<Option<V>> {
+        let routes = RadixRule::expand(&path, config)?;
+        self.check(&path, &routes, priority, config, warnings)?;
+
+        let mut routes = routes.into_iter();
+        let link = match routes.next() {
+            Some((link, _)) => link,
+            None => unreachable!()
+        };
+
+        for (route, defaults) in routes {
+            let slot = self.locate_with(route, priority, config)?;
+            slot.alias = Some(Box::new(RadixAlias { link: link.clone(), defaults }));
+        }
+
+        let slot = self.locate_with(link, priority, config)?;
+        let prev = slot.data.take();
+        slot.path = path;
+        slot.data = Some(data);
+        Ok(prev)
+    }
+
+    /// Find the node that holds the raw path, creating the missing nodes along the way.
+    /// The returned node is empty if the path has never been inserted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{node::RadixNode, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut node = RadixNode::default();
+    ///     node.insert("/api/v1", "v1")?;
+    ///
+    ///     assert_eq!(node.locate("/api/v1")?.data, Some("v1"));
+    ///     assert_eq!(node.locate("/api/v1")?.rule, b"/api/v1");
+    ///     assert_eq!(node.locate("/api/:id")?.data, None);
+    ///     assert_eq!(node.locate("/api/:id")?.rule, b":id");
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn locate(&mut self, path: impl Into<Bytes>) -> RadixResult<&mut RadixNode<V>> {
+        self.locate_with(path.into(), None, &RadixConfig::default())
+    }
+
+    /// Internal use only, reject the routes which conflict with the existing special nodes
+    /// before any node is created, or record the conflicts as warnings in the lenient mode
+    pub(crate) fn check(&self, path: &Bytes, routes: &[(Bytes, Defaults)], priority: Option<i32>, config: &RadixConfig, warnings: &mut Vec<RadixError>) -> RadixResult<()> {
+        for (route, _) in routes {
+            match self.probe(path, route.clone(), priority, config) {
+                Err(err @ RadixError::Conflict { .. }) if config.lenient => warnings.push(err),
+                Err(err) => return Err(err),
+                Ok(_) => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Internal use only, walk along the route without creating nodes and find the special
+    /// sibling which conflicts with it, a new branch has nothing to conflict with. Changing the
+    /// priority of a special node which other patterns pass through conflicts with them too
+    fn probe(&self, path: &Bytes, route: Bytes, priority: Option<i32>, config: &RadixConfig) -> RadixResult<()> {
+        let rules = Self::split(&route, config)?;
+        let last = rules.iter().rposition(RadixRule::is_special);
+        let mut slot = self;
+
+        for (index, next) in rules.iter().enumerate() {
+            slot.clash(path, &route, &rules, index, priority.filter(|_| Some(index) == last))?;
+
+            match next.is_special() {
+                true => match slot.next.special.get(next.origin()) {
+                    Some(node) => slot = node,
+                    None => return Ok(()),
+                }
+                false => {
+                    let mut text = next.origin().as_ref();
+
+                    while !text.is_empty() {
+                        let node = match slot.next.regular.get(slot.next.index(text[0])) {
+                            Some(node) => node,
+                            None => return Ok(()),
+                        };
+
+                        // the node would be divided, so the rest is a new branch
+                        let share = node.rule.longest(text, false).map_or(0, |share| share.len());
+                        if share != node.rule.origin().len() {
+                            return Ok(());
+                        }
+
+                        text = &text[share..];
+                        slot = node;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Internal use only, find the special sibling of the rule at the index which conflicts with
+    /// the route, or which other patterns share if its priority changes
+    fn clash(&self, path: &Bytes, route: &Bytes, rules: &[RadixRule], index: usize, priority: Option<i32>) -> RadixResult<()> {
+        let next = &rules[index];
+
+        if let Some(found) = self.next.conflict(next, &rules[index + 1..], priority) {
+            let existing = found.iter().next().map_or_else(|| found.rule.origin().clone(), |node| node.path.clone());
+            return Err(RadixError::Conflict { existing, new: route.clone() });
+        }
+
+        let shared = priority.and_then(|priority| self.next.special.get(next.origin()).filter(|node| node.priority != priority));
+        if let Some(other) = shared.and_then(|node| node.iter().find(|other| other.path != path)) {
+            return Err(RadixError::Conflict { existing: other.path.clone(), new: route.clone() });
+        }
+
+        Ok(())
+    }
+
+    /// Internal use only, same as check and locate_with in a single walk for the path without
+    /// optional segments. The conflict is checked before the level is touched, so a rejected
+    /// path creates no nodes
+    pub(crate) fn claim(&mut self, path: &Bytes, config: &RadixConfig, warnings: &mut Vec<RadixError>) -> RadixResult<&mut RadixNode<V>> {
+        let rules = Self::split(path, config)?;
+        let mut check = true;
+        let mut slot = self;
+
+        for (index, next) in rules.iter().enumerate() {
+            // only the first conflict is reported, like check does
+            if check {
+                match slot.clash(path, path, &rules, index, None) {
+                    Err(err @ RadixError::Conflict { .. }) if config.lenient => {
+                        warnings.push(err);
+                        check = false;
+                    }
+                    Err(err) => return Err(err),
+                    Ok(_) => {}
+                }
+            }
+
+            slot = slot.next.insert(next.clone())?;
+        }
+
+        Ok(slot)
+    }
+
+    /// Internal use only, locate the node and update the priority if specified. The priority
+    /// belongs to the route, so only its last special node takes it, the shared ones are kept
+    pub(crate) fn locate_with(&mut self, route: Bytes, priority: Option<i32>, config: &RadixConfig) -> RadixResult<&mut RadixNode<V>> {
+        let rules = Self::split(&route, config)?;
+        let last = rules.iter().rposition(RadixRule::is_special);
         let mut slot = self;
 
+        // insert the fragments via pack one by one, the last one indicates the target node
+        for (index, next) in rules.into_iter().enumerate() {
+            slot = match priority.filter(|_| Some(index) == last) {
+                Some(priority) => slot.next.insert_with_priority(next, priority)?,
+                None => slot.next.insert(next)?,
+            };
+        }
+
+        Ok(slot)
+    }
+
+    /// Internal use only, extract the rules of the route in order, errors point into the route
+    fn split(route: &Bytes, config: &RadixConfig) -> RadixResult<Vec<RadixRule>> {
+        let mut frag = route.clone();
+        let mut rules = vec![];
+
         loop {
-            // extract the next path fragment and insert it via pack
-            let next = RadixRule::try_from(frag.clone())?;
-            let used = next.origin().clone();
-            slot = slot.next.insert(next)?;
-
-            // encountering a data node indicates completion of insertion
-            if used.len() == frag.len() {
-                let prev = slot.data.take();
-                slot.path = path;
-                slot.data = Some(data);
-                return Ok(prev);
+            let (next, used) = RadixRule::consume(frag.clone(), config).map_err(|err| err.at(route, route.len() - frag.len(), frag.len()))?;
+            rules.push(next);
+
+            if used == frag.len() {
+                return Ok(rules);
             }
 
-            frag = frag.slice(used.len()..);
+            frag = frag.slice(used..);
         }
     }
 
@@ -221,12 +456,17 @@ impl<V> RadixNode<V> {
     /// - If `data` is false, the function returns the deepest node that matches the path as far as
     ///   possible, regardless of whether it is a data node or not.
     ///
+    /// The regular node is tried first, if it dead-ends then the special nodes on the same level
+    /// are tried in descending priority, or in the order of regex, param and glob by default,
+    /// and the captures pushed by the abandoned branch are rolled back.
+    ///
     /// # Examples
     ///
     /// ```
     /// use bytes::Bytes;
-    /// use radixmap::{node::RadixNode, RadixResult};
+    /// use radixmap::{node::RadixNode, params::Params, RadixResult};
     ///
+    /// # #[cfg(all(feature = "glob", feature = "regex"))]
     /// fn main() -> RadixResult<()> {
     ///     let mut node = RadixNode::default();
     ///     node.insert("/api", "api")?;
@@ -236,73 +476,107 @@ impl<V> RadixNode<V> {
     ///     node.insert("/api/v2/user/{id:[^0-9]+}", "user2")?;
     ///     node.insert("/api/v3/user/*cde", "user3")?;
     ///
-    ///     assert_eq!(node.lookup(b"/", false, false, &mut vec![], false).map(|node| node.rule.origin()), Some(&Bytes::from("/api")));
-    ///     assert_eq!(node.lookup(b"/api", false, false, &mut vec![], false).map(|node| node.rule.origin()), Some(&Bytes::from("/api")));
-    ///     assert_eq!(node.lookup(b"/api/v", false, false, &mut vec![], false).map(|node| node.rule.origin()), Some(&Bytes::from("/v")));
-    ///     assert_eq!(node.lookup(b"/api/v1", false, false, &mut vec![], false).map(|node| node.rule.origin()), Some(&Bytes::from("1")));
-    ///     assert_eq!(node.lookup(b"/api/v2", false, false, &mut vec![], false).map(|node| node.rule.origin()), Some(&Bytes::from("2")));
-    ///     assert_eq!(node.lookup(b"/api/v3", false, false, &mut vec![], false).map(|node| node.rule.origin()), Some(&Bytes::from("3/user/")));
-    ///
-    ///     assert_eq!(node.lookup(b"/", true, false, &mut vec![], false).map(|node| node.rule.origin()), None);
-    ///     assert_eq!(node.lookup(b"/api", true, false, &mut vec![], false).map(|node| node.rule.origin()), Some(&Bytes::from("/api")));
-    ///     assert_eq!(node.lookup(b"/api/v", true, false, &mut vec![], false).map(|node| node.rule.origin()), None);
-    ///     assert_eq!(node.lookup(b"/api/v1", true, false, &mut vec![], false).map(|node| node.rule.origin()), Some(&Bytes::from("1")));
-    ///     assert_eq!(node.lookup(b"/api/v2", true, false, &mut vec![], false).map(|node| node.rule.origin()), Some(&Bytes::from("2")));
-    ///     assert_eq!(node.lookup(b"/api/v1/user/", true, false, &mut vec![], false).map(|node| node.rule.origin()), None);
-    ///     assert_eq!(node.lookup(b"/api/v1/user/12345", true, false, &mut vec![], false).map(|node| node.rule.origin()), Some(&Bytes::from(":id")));
-    ///     assert_eq!(node.lookup(b"/api/v2/user/12345", true, false, &mut vec![], false).map(|node| node.rule.origin()), None);
-    ///     assert_eq!(node.lookup(b"/api/v2/user/abcde", true, false, &mut vec![], false).map(|node| node.rule.origin()), Some(&Bytes::from("{id:[^0-9]+}")));
-    ///     assert_eq!(node.lookup(b"/api/v3/user/12345", true, false, &mut vec![], false).map(|node| node.rule.origin()), None);
-    ///     assert_eq!(node.lookup(b"/api/v3/user/abcde", true, false, &mut vec![], false).map(|node| node.rule.origin()), Some(&Bytes::from("*cde")));
+    ///     assert_eq!(node.lookup(b"/", false, false, &mut Params::new(), false).map(|node| node.rule.origin()), Some(&Bytes::from("/api")));
+    ///     assert_eq!(node.lookup(b"/api", false, false, &mut Params::new(), false).map(|node| node.rule.origin()), Some(&Bytes::from("/api")));
+    ///     assert_eq!(node.lookup(b"/api/v", false, false, &mut Params::new(), false).map(|node| node.rule.origin()), Some(&Bytes::from("/v")));
+    ///     assert_eq!(node.lookup(b"/api/v1", false, false, &mut Params::new(), false).map(|node| node.rule.origin()), Some(&Bytes::from("1")));
+    ///     assert_eq!(node.lookup(b"/api/v2", false, false, &mut Params::new(), false).map(|node| node.rule.origin()), Some(&Bytes::from("2")));
+    ///     assert_eq!(node.lookup(b"/api/v3", false, false, &mut Params::new(), false).map(|node| node.rule.origin()), Some(&Bytes::from("3/user/")));
+    ///
+    ///     assert_eq!(node.lookup(b"/", true, false, &mut Params::new(), false).map(|node| node.rule.origin()), None);
+    ///     assert_eq!(node.lookup(b"/api", true, false, &mut Params::new(), false).map(|node| node.rule.origin()), Some(&Bytes::from("/api")));
+    ///     assert_eq!(node.lookup(b"/api/v", true, false, &mut Params::new(), false).map(|node| node.rule.origin()), None);
+    ///     assert_eq!(node.lookup(b"/api/v1", true, false, &mut Params::new(), false).map(|node| node.rule.origin()), Some(&Bytes::from("1")));
+    ///     assert_eq!(node.lookup(b"/api/v2", true, false, &mut Params::new(), false).map(|node| node.rule.origin()), Some(&Bytes::from("2")));
+    ///     assert_eq!(node.lookup(b"/api/v1/user/", true, false, &mut Params::new(), false).map(|node| node.rule.origin()), None);
+    ///     assert_eq!(node.lookup(b"/api/v1/user/12345", true, false, &mut Params::new(), false).map(|node| node.rule.origin()), Some(&Bytes::from(":id")));
+    ///     assert_eq!(node.lookup(b"/api/v2/user/12345", true, false, &mut Params::new(), false).map(|node| node.rule.origin()), None);
+    ///     assert_eq!(node.lookup(b"/api/v2/user/abcde", true, false, &mut Params::new(), false).map(|node| node.rule.origin()), Some(&Bytes::from("{id:[^0-9]+}")));
+    ///     assert_eq!(node.lookup(b"/api/v3/user/12345", true, false, &mut Params::new(), false).map(|node| node.rule.origin()), None);
+    ///     assert_eq!(node.lookup(b"/api/v3/user/abcde", true, false, &mut Params::new(), false).map(|node| node.rule.origin()), Some(&Bytes::from("*cde")));
+    ///
+    ///     // backtracking from the plain branch
+    ///     node.insert("/user/new", "new")?;
+    ///     node.insert("/user/:id", "id")?;
+    ///     node.insert("/user/:id/{tab:[a-z]+}", "tab")?;
+    ///     node.insert("/user/*", "glob")?;
+    ///
+    ///     let mut capt = Params::new();
+    ///     assert_eq!(node.lookup(b"/user/new", true, false, &mut capt, true).map(|node| node.rule.origin()), Some(&Bytes::from("new")));
+    ///     assert_eq!(capt, vec![]);
+    ///
+    ///     let mut capt = Params::new();
+    ///     assert_eq!(node.lookup(b"/user/newer", true, false, &mut capt, true).map(|node| node.rule.origin()), Some(&Bytes::from(":id")));
+    ///     assert_eq!(capt, vec![(Bytes::from("id"), "newer".as_bytes())]);
+    ///
+    ///     let mut capt = Params::new();
+    ///     assert_eq!(node.lookup(b"/user/new/123", true, false, &mut capt, true).map(|node| node.rule.origin()), Some(&Bytes::from("*")));
+    ///     assert_eq!(capt, vec![(Bytes::from("*"), "new/123".as_bytes())]);
     ///
     ///     Ok(())
     /// }
+    /// # #[cfg(not(all(feature = "glob", feature = "regex")))]
+    /// # fn main() {}
     /// ```
-    pub fn lookup<'u>(&self, mut path: &'u [u8], data: bool, raw: bool, capture: &mut Vec<(Bytes, &'u [u8])>, enable: bool) -> Option<&RadixNode<V>> {
-        let mut current = self;
-
-        loop {
-            // prefix must be part of the current node
-            let share = match current.rule.longest(path, raw) {
-                Some(val) => val,
-                None => return None,
-            };
-            let equal = (!raw && current.rule.is_special()) || current.rule.origin().len() == share.len();
-            if share.len() != path.len() && !equal {
-                return None
-            }
+    #[inline]
+    pub fn lookup<'u>(&self, path: &'u [u8], data: bool, raw: bool, capture: &mut Params<'u>, enable: bool) -> Option<&RadixNode<V>> {
+        self.trace(path, data, raw, capture, enable, None)
+    }
 
-            if enable {
-                let ident = current.rule.identity();
-                if !ident.is_empty() {
-                    capture.push((ident.clone(), share));
-                }
-            }
+    /// Find the deepest data node whose pattern matches a prefix of the path, and return it with
+    /// the unmatched remainder. The lookup runs as usual and remembers the data nodes it passes
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{node::RadixNode, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut node = RadixNode::default();
+    ///     node.insert("/", "root")?;
+    ///     node.insert("/static", "static")?;
+    ///     node.insert("/user/:id", "user")?;
+    ///
+    ///     assert_eq!(node.lookup_prefix(b"/static/app.css").map(|(node, rest)| (node.data, rest)), Some((Some("static"), b"/app.css".as_ref())));
+    ///     assert_eq!(node.lookup_prefix(b"/user/1/profile").map(|(node, rest)| (node.data, rest)), Some((Some("user"), b"/profile".as_ref())));
+    ///     assert_eq!(node.lookup_prefix(b"/user").map(|(node, rest)| (node.data, rest)), Some((Some("root"), b"user".as_ref())));
+    ///     assert_eq!(node.lookup_prefix(b"user").map(|(node, rest)| (node.data, rest)), None);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn lookup_prefix<'u>(&self, path: &'u [u8]) -> Option<(&RadixNode<V>, &'u [u8])> {
+        let mut track = Track::partial();
+        self.trace(path, true, false, &mut Params::new(), false, Some(&mut track));
+        track.deepest.map(|(node, rest)| (node, &path[path.len() - rest..]))
+    }
 
-            // trim the shared and continue lookup
-            path = &path[share.len()..];
+    /// Internal use only, same as lookup, and record what the walk passes by in the track
+    #[inline]
+    pub(crate) fn trace<'n, 'u>(&'n self, path: &'u [u8], data: bool, raw: bool, capture: &mut Params<'u>, enable: bool, mut track: Option<&mut Track<&'n RadixNode<V>>>) -> Option<&'n RadixNode<V>> {
+        if enable {
+            capture.bind(path);
+        }
 
-            let byte = match path.first() {
-                Some(&val) => val as usize,
-                None if data && (!equal || current.is_empty()) => 0, // data node must be an exact match
-                None => return Some(current),
-            };
+        if let Some(track) = track.as_deref_mut() {
+            track.steps.clear();
+        }
 
-            // find regular node by vector map
-            if let Some(node) = current.next.regular.get(byte) {
-                current = node;
-                continue;
-            }
+        self.walk(path, data, raw, capture, enable, track)
+    }
 
-            // find special node, if not then terminate
-            for node in current.next.special.values() {
-                if let Some(find) = node.lookup(path, data, raw, capture, enable) {
-                    return Some(find);
-                }
-            }
+    /// Internal use only, follow the steps recorded by the walk down to the node
+    pub(crate) fn descend(&mut self, steps: &[Step]) -> Option<&mut RadixNode<V>> {
+        let mut current = self;
 
-            return None;
+        for step in steps {
+            current = match *step {
+                Step::Regular(byte) => current.next.regular.get_mut(current.next.index(byte))?,
+                Step::Special(index) => current.next.special.get_index_mut(index)?.1,
+            };
         }
+
+        Some(current)
     }
 
     /// Same as lookup
@@ -311,8 +585,9 @@ impl<V> RadixNode<V> {
     ///
     /// ```
     /// use bytes::Bytes;
-    /// use radixmap::{node::RadixNode, RadixResult};
+    /// use radixmap::{node::RadixNode, params::Params, RadixResult};
     ///
+    /// # #[cfg(all(feature = "glob", feature = "regex"))]
     /// fn main() -> RadixResult<()> {
     ///     let mut node = RadixNode::default();
     ///     node.insert("/api", "api")?;
@@ -322,73 +597,119 @@ impl<V> RadixNode<V> {
     ///     node.insert("/api/v2/user/{id:[^0-9]+}", "user2")?;
     ///     node.insert("/api/v3/user/*cde", "user3")?;
     ///
-    ///     assert_eq!(node.lookup_mut(b"/", false, false, &mut vec![], false).map(|node| node.rule.origin()), Some(&Bytes::from("/api")));
-    ///     assert_eq!(node.lookup_mut(b"/api", false, false, &mut vec![], false).map(|node| node.rule.origin()), Some(&Bytes::from("/api")));
-    ///     assert_eq!(node.lookup_mut(b"/api/v", false, false, &mut vec![], false).map(|node| node.rule.origin()), Some(&Bytes::from("/v")));
-    ///     assert_eq!(node.lookup_mut(b"/api/v1", false, false, &mut vec![], false).map(|node| node.rule.origin()), Some(&Bytes::from("1")));
-    ///     assert_eq!(node.lookup_mut(b"/api/v2", false, false, &mut vec![], false).map(|node| node.rule.origin()), Some(&Bytes::from("2")));
-    ///     assert_eq!(node.lookup_mut(b"/api/v3", false, false, &mut vec![], false).map(|node| node.rule.origin()), Some(&Bytes::from("3/user/")));
-    ///
-    ///     assert_eq!(node.lookup_mut(b"/", true, false, &mut vec![], false).map(|node| node.rule.origin()), None);
-    ///     assert_eq!(node.lookup_mut(b"/api", true, false, &mut vec![], false).map(|node| node.rule.origin()), Some(&Bytes::from("/api")));
-    ///     assert_eq!(node.lookup_mut(b"/api/v", true, false, &mut vec![], false).map(|node| node.rule.origin()), None);
-    ///     assert_eq!(node.lookup_mut(b"/api/v1", true, false, &mut vec![], false).map(|node| node.rule.origin()), Some(&Bytes::from("1")));
-    ///     assert_eq!(node.lookup_mut(b"/api/v2", true, false, &mut vec![], false).map(|node| node.rule.origin()), Some(&Bytes::from("2")));
-    ///     assert_eq!(node.lookup_mut(b"/api/v1/user/", true, false, &mut vec![], false).map(|node| node.rule.origin()), None);
-    ///     assert_eq!(node.lookup_mut(b"/api/v1/user/12345", true, false, &mut vec![], false).map(|node| node.rule.origin()), Some(&Bytes::from(":id")));
-    ///     assert_eq!(node.lookup_mut(b"/api/v2/user/12345", true, false, &mut vec![], false).map(|node| node.rule.origin()), None);
-    ///     assert_eq!(node.lookup_mut(b"/api/v2/user/abcde", true, false, &mut vec![], false).map(|node| node.rule.origin()), Some(&Bytes::from("{id:[^0-9]+}")));
-    ///     assert_eq!(node.lookup_mut(b"/api/v3/user/12345", true, false, &mut vec![], false).map(|node| node.rule.origin()), None);
-    ///     assert_eq!(node.lookup_mut(b"/api/v3/user/abcde", true, false, &mut vec![], false).map(|node| node.rule.origin()), Some(&Bytes::from("*cde")));
+    ///     assert_eq!(node.lookup_mut(b"/", false, false, &mut Params::new(), false).map(|node| node.rule.origin()), Some(&Bytes::from("/api")));
+    ///     assert_eq!(node.lookup_mut(b"/api", false, false, &mut Params::new(), false).map(|node| node.rule.origin()), Some(&Bytes::from("/api")));
+    ///     assert_eq!(node.lookup_mut(b"/api/v", false, false, &mut Params::new(), false).map(|node| node.rule.origin()), Some(&Bytes::from("/v")));
+    ///     assert_eq!(node.lookup_mut(b"/api/v1", false, false, &mut Params::new(), false).map(|node| node.rule.origin()), Some(&Bytes::from("1")));
+    ///     assert_eq!(node.lookup_mut(b"/api/v2", false, false, &mut Params::new(), false).map(|node| node.rule.origin()), Some(&Bytes::from("2")));
+    ///     assert_eq!(node.lookup_mut(b"/api/v3", false, false, &mut Params::new(), false).map(|node| node.rule.origin()), Some(&Bytes::from("3/user/")));
+    ///
+    ///     assert_eq!(node.lookup_mut(b"/", true, false, &mut Params::new(), false).map(|node| node.rule.origin()), None);
+    ///     assert_eq!(node.lookup_mut(b"/api", true, false, &mut Params::new(), false).map(|node| node.rule.origin()), Some(&Bytes::from("/api")));
+    ///     assert_eq!(node.lookup_mut(b"/api/v", true, false, &mut Params::new(), false).map(|node| node.rule.origin()), None);
+    ///     assert_eq!(node.lookup_mut(b"/api/v1", true, false, &mut Params::new(), false).map(|node| node.rule.origin()), Some(&Bytes::from("1")));
+    ///     assert_eq!(node.lookup_mut(b"/api/v2", true, false, &mut Params::new(), false).map(|node| node.rule.origin()), Some(&Bytes::from("2")));
+    ///     assert_eq!(node.lookup_mut(b"/api/v1/user/", true, false, &mut Params::new(), false).map(|node| node.rule.origin()), None);
+    ///     assert_eq!(node.lookup_mut(b"/api/v1/user/12345", true, false, &mut Params::new(), false).map(|node| node.rule.origin()), Some(&Bytes::from(":id")));
+    ///     assert_eq!(node.lookup_mut(b"/api/v2/user/12345", true, false, &mut Params::new(), false).map(|node| node.rule.origin()), None);
+    ///     assert_eq!(node.lookup_mut(b"/api/v2/user/abcde", true, false, &mut Params::new(), false).map(|node| node.rule.origin()), Some(&Bytes::from("{id:[^0-9]+}")));
+    ///     assert_eq!(node.lookup_mut(b"/api/v3/user/12345", true, false, &mut Params::new(), false).map(|node| node.rule.origin()), None);
+    ///     assert_eq!(node.lookup_mut(b"/api/v3/user/abcde", true, false, &mut Params::new(), false).map(|node| node.rule.origin()), Some(&Bytes::from("*cde")));
+    ///
+    ///     // backtracking from the plain branch
+    ///     node.insert("/user/new", "new")?;
+    ///     node.insert("/user/:id", "id")?;
+    ///
+    ///     assert_eq!(node.lookup_mut(b"/user/new", true, false, &mut Params::new(), false).map(|node| node.rule.origin()), Some(&Bytes::from("new")));
+    ///     assert_eq!(node.lookup_mut(b"/user/newer", true, false, &mut Params::new(), false).map(|node| node.rule.origin()), Some(&Bytes::from(":id")));
     ///
     ///     Ok(())
     /// }
+    /// # #[cfg(not(all(feature = "glob", feature = "regex")))]
+    /// # fn main() {}
     /// ```
-    pub fn lookup_mut<'u>(&mut self, mut path: &'u [u8], data: bool, raw: bool, capture: &mut Vec<(Bytes, &'u [u8])>, enable: bool) -> Option<&mut RadixNode<V>> {
-        let mut current = self;
-
-        loop {
-            // prefix must be part of the current node
-            let share = match current.rule.longest(path, raw) {
-                Some(val) => val,
-                None => return None,
-            };
-            let equal = (!raw && current.rule.is_special()) || current.rule.origin().len() == share.len();
-            if share.len() != path.len() && !equal {
-                return None
-            }
-
-            if enable {
-                let ident = current.rule.identity();
-                if !ident.is_empty() {
-                    capture.push((ident.clone(), share));
-                }
-            }
+    pub fn lookup_mut<'u>(&mut self, path: &'u [u8], data: bool, raw: bool, capture: &mut Params<'u>, enable: bool) -> Option<&mut RadixNode<V>> {
+        let mut track = Track::trail();
+        self.trace(path, data, raw, capture, enable, Some(&mut track))?;
+        self.descend(&track.steps)
+    }
 
-            // trim the shared and continue lookup
-            path = &path[share.len()..];
+    /// Internal use only, the positions where the param may stop early, which are the literals
+    /// following it in the patterns, shortest first
+    pub(crate) fn cuts<'a>(&'a self, path: &'a [u8]) -> impl Iterator<Item = usize> + 'a {
+        let data = match self.next.regular.is_empty() {
+            true => None,
+            false => self.rule.segment(path),
+        };
 
-            let byte = match path.first() {
-                Some(&val) => val as usize,
-                None if data && (!equal || current.is_empty()) => 0, // data node must be an exact match
-                None => return Some(current),
-            };
+        let data = data.unwrap_or_default();
 
-            // find regular node by vector map
-            if let Some(node) = current.next.regular.get_mut(byte) {
-                current = node;
-                continue;
-            }
+        (1..data.len()).filter(move |&pos| self.cut(data, pos).is_some())
+    }
 
-            // find special node, if not then terminate
-            for node in current.next.special.values_mut() {
-                if let Some(find) = node.lookup_mut(path, data, raw, capture, enable) {
-                    return Some(find);
-                }
-            }
+    /// Resolve the alias node to the data node it stands for, and append the default values to
+    /// the captures. It should be called on the root node
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{node::RadixNode, params::Params, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut node = RadixNode::default();
+    ///     node.insert("/page/:n=1", "page")?;
+    ///
+    ///     let mut capt = Params::new();
+    ///     let alias = node.lookup(b"/page", true, false, &mut capt, true);
+    ///     let found = alias.and_then(|alias| node.follow(alias, &mut capt));
+    ///
+    ///     assert_eq!(found.and_then(|node| node.data), Some("page"));
+    ///     assert_eq!(capt.get("n"), Some(b"1".as_ref()));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn follow<'n>(&'n self, node: &'n RadixNode<V>, capture: &mut Params<'_>) -> Option<&'n RadixNode<V>> {
+        RadixWalk::follow(node, self, capture, None)
+    }
 
-            return None;
-        }
+    /// Iterate all data nodes matching the path along with their captures. Nodes are visited
+    /// depth-first in the same order as lookup tries them
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use radixmap::{node::RadixNode, params::Params, RadixResult};
+    ///
+    /// # #[cfg(feature = "glob")]
+    /// fn main() -> RadixResult<()> {
+    ///     let mut node = RadixNode::default();
+    ///     node.insert("/user/new", "new")?;
+    ///     node.insert("/user/:id", "id")?;
+    ///     node.insert("/user/*", "glob")?;
+    ///     node.insert("/*", "root")?;
+    ///
+    ///     let mut iter = node.matches(b"/user/new");
+    ///     assert_eq!(iter.next().map(|(node, capt)| (node.data, capt)), Some((Some("new"), Params::new())));
+    ///     assert_eq!(iter.next().map(|(node, capt)| (node.data, capt)), Some((Some("id"), Params::from(vec![(Bytes::from("id"), "new".as_bytes())]))));
+    ///     assert_eq!(iter.next().map(|(node, capt)| (node.data, capt)), Some((Some("glob"), Params::from(vec![(Bytes::from("*"), "new".as_bytes())]))));
+    ///     assert_eq!(iter.next().map(|(node, capt)| (node.data, capt)), Some((Some("root"), Params::from(vec![(Bytes::from("*"), "user/new".as_bytes())]))));
+    ///     assert!(iter.next().is_none());
+    ///
+    ///     assert_eq!(node.matches(b"/user/new/1").count(), 2);
+    ///     assert_eq!(node.matches(b"/user").count(), 1);
+    ///     assert_eq!(node.matches(b"").count(), 0);
+    ///
+    ///     Ok(())
+    /// }
+    /// # #[cfg(not(feature = "glob"))]
+    /// # fn main() {}
+    /// ```
+    #[inline]
+    pub fn matches<'u>(&self, path: &'u [u8]) -> Matches<'_, 'u, V> {
+        Matches::from((self, path))
     }
 
     /// Divide the node into two parts
@@ -417,14 +738,177 @@ impl<V> RadixNode<V> {
     #[inline]
     pub fn divide(&mut self, len: usize) -> RadixResult<RadixNode<V>> {
         Ok(RadixNode {
-            path: std::mem::take(&mut self.path),
+            path: core::mem::take(&mut self.path),
             data: self.data.take(),
 
             rule: self.rule.divide(len)?,
-            next: std::mem::take(&mut self.next),
+            priority: self.priority,
+            alias: self.alias.take(),
+            next: core::mem::take(&mut self.next),
         })
     }
 
+    /// Merge the child node into this empty node, the reverse of divide
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{node::RadixNode, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut node = RadixNode::try_from(("/api", 12345))?;
+    ///     let frag = node.divide(1)?;
+    ///
+    ///     node.merge(frag)?;
+    ///
+    ///     assert_eq!(node.rule, b"/api");
+    ///     assert_eq!(node.data, Some(12345));
+    ///
+    ///     // data node can't be merged
+    ///     assert!(node.merge(RadixNode::try_from(("/v1", 0))?).is_err());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn merge(&mut self, node: RadixNode<V>) -> RadixResult<()> {
+        if !self.is_empty() || self.is_alias() {
+            return Err(RadixError::RuleUnmergeable);
+        }
+
+        self.rule.merge(&node.rule)?;
+        self.path = node.path;
+        self.data = node.data;
+        self.alias = node.alias;
+        self.next = node.next;
+
+        Ok(())
+    }
+
+    /// Remove the empty leaves and merge the plain fragments which were divided before
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{node::RadixNode, params::Params, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut node = RadixNode::default();
+    ///     node.insert("/api", "api")?;
+    ///     node.insert("/api/v1", "v1")?;
+    ///     node.insert("/api/v2", "v2")?;
+    ///     node.insert("/api/:id", "id")?;
+    ///
+    ///     node.lookup_mut(b"/api", true, true, &mut Params::new(), false).map(|node| node.data.take());
+    ///     node.lookup_mut(b"/api/v2", true, true, &mut Params::new(), false).map(|node| node.data.take());
+    ///     node.lookup_mut(b"/api/:id", true, true, &mut Params::new(), false).map(|node| node.data.take());
+    ///
+    ///     assert_eq!(node.iter().with_empty().count(), 7);
+    ///
+    ///     node.compact();
+    ///
+    ///     let mut iter = node.iter().with_empty();
+    ///     assert_eq!(iter.next().map(|node| node.rule.origin().as_ref()), Some(b"".as_ref()));
+    ///     assert_eq!(iter.next().map(|node| node.rule.origin().as_ref()), Some(b"/api/v1".as_ref()));
+    ///     assert!(iter.next().is_none());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn compact(&mut self) {
+        for node in self.next.iter_mut() {
+            node.compact();
+        }
+
+        self.next.regular.retain(|_, node| !node.reduce());
+        self.next.special.retain(|_, node| !node.reduce());
+    }
+
+    /// Same as compact, but only touch the nodes along the raw path
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{node::RadixNode, params::Params, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut node = RadixNode::default();
+    ///     node.insert("/api/v1", "v1")?;
+    ///     node.insert("/api/v2", "v2")?;
+    ///     node.insert("/api/v3/:id", "id")?;
+    ///
+    ///     node.lookup_mut(b"/api/v2", true, true, &mut Params::new(), false).map(|node| node.data.take());
+    ///     node.lookup_mut(b"/api/v3/:id", true, true, &mut Params::new(), false).map(|node| node.data.take());
+    ///
+    ///     node.prune(b"/api/v2");
+    ///     assert_eq!(node.iter().with_empty().count(), 5);
+    ///
+    ///     node.prune(b"/api/v3/:id");
+    ///     assert_eq!(node.iter().with_empty().count(), 2);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn prune(&mut self, path: &[u8]) {
+        // the path must cover the whole fragment of current node
+        let size = self.rule.origin().len();
+        let path = match self.rule.longest(path, true) {
+            Some(share) if share.len() == size => &path[size..],
+            _ => return,
+        };
+
+        let byte = match path.first() {
+            Some(&val) => self.next.index(val),
+            None => return,
+        };
+
+        if let Some(node) = self.next.regular.get_mut(byte) {
+            node.prune(path);
+
+            if node.reduce() {
+                self.next.regular.remove(byte);
+            }
+        }
+
+        self.next.special.retain(|frag, node| {
+            if !path.starts_with(frag) {
+                return true;
+            }
+
+            node.prune(path);
+            !node.reduce()
+        });
+    }
+
+    /// Internal use only, absorb the only plain child and report if the node can be dropped
+    fn reduce(&mut self) -> bool {
+        if !self.is_empty() || self.is_alias() {
+            return false;
+        }
+
+        if self.next.is_empty() {
+            return true;
+        }
+
+        if self.rule.is_plain() && self.next.special.is_empty() && self.next.regular.len() == 1 {
+            let next = self.next.regular.drain().next();
+
+            // both rules are plain, merging won't fail
+            if let Some((_, node)) = next {
+                let _ = self.merge(node);
+            }
+        }
+
+        false
+    }
+
+    /// Compact the tree and release the unused capacity
+    #[inline]
+    pub fn shrink_to_fit(&mut self) {
+        self.compact();
+        self.next.shrink_to_fit();
+    }
+
     /// Clear the nodes but preserve its capacity
     ///
     /// # Examples
@@ -450,6 +934,7 @@ impl<V> RadixNode<V> {
         self.path.clear();
         self.data = None;
         self.rule = RadixRule::default();
+        self.alias = None;
         self.next.clear();
     }
 }
@@ -471,7 +956,7 @@ impl<V> RadixNode<V> {
 impl<V> From<RadixRule> for RadixNode<V> {
     #[inline]
     fn from(rule: RadixRule) -> Self {
-        Self { path: Bytes::new(), data: None, rule, next: Default::default() }
+        Self { path: Bytes::new(), data: None, rule, priority: 0, alias: None, next: Default::default() }
     }
 }
 
@@ -494,7 +979,7 @@ impl<V> TryFrom<(Bytes, V)> for RadixNode<V> {
 
     #[inline]
     fn try_from((path, data): (Bytes, V)) -> RadixResult<Self> {
-        Ok(Self { path: path.clone(), data: Some(data), rule: RadixRule::try_from(path)?, next: Default::default() })
+        Ok(Self { path: path.clone(), data: Some(data), rule: RadixRule::try_from(path)?, priority: 0, alias: None, next: Default::default() })
     }
 }
 
@@ -528,7 +1013,7 @@ impl<V> TryFrom<(&'static str, V)> for RadixNode<V> {
 impl<V> Default for RadixNode<V> {
     #[inline]
     fn default() -> Self {
-        Self { path: Bytes::new(), data: None, rule: RadixRule::default(), next: pack::RadixPack::default() }
+        Self { path: Bytes::new(), data: None, rule: RadixRule::default(), priority: 0, alias: None, next: pack::RadixPack::default() }
     }
 }
 
@@ -539,6 +1024,7 @@ impl<V> Default for RadixNode<V> {
 /// ```
 /// use radixmap::{node::RadixNode, RadixResult};
 ///
+/// # #[cfg(all(feature = "glob", feature = "regex"))]
 /// fn main() -> RadixResult<()> {
 ///     assert_eq!(format!("{:?}", RadixNode::try_from((r"/api", ()))?).as_str(), r"Plain(/api)");
 ///     assert_eq!(format!("{:?}", RadixNode::try_from((r":id", ()))?).as_str(), r"Param(:id)");
@@ -547,9 +1033,11 @@ impl<V> Default for RadixNode<V> {
 ///
 ///     Ok(())
 /// }
+/// # #[cfg(not(all(feature = "glob", feature = "regex")))]
+/// # fn main() {}
 /// ```
 impl<V> Debug for RadixNode<V> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         self.rule.fmt(f)
     }
 }
@@ -576,6 +1064,8 @@ impl<V: Clone> Clone for RadixNode<V> {
             path: self.path.clone(),
             data: self.data.clone(),
             rule: self.rule.clone(),
+            priority: self.priority,
+            alias: self.alias.clone(),
             next: self.next.clone(),
         }
     }
@@ -583,6 +1073,306 @@ impl<V: Clone> Clone for RadixNode<V> {
 
 // -----------------------------------------------------------------------------
 
+/// Internal use only, a step from a node down to one of its children
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Step {
+    /// The regular child which the byte leads to
+    Regular(u8),
+
+    /// The special child at the index
+    Special(usize),
+}
+
+/// Internal use only, what the walk records on the way besides the node it finds
+pub(crate) struct Track<T> {
+    /// Record the deepest data node passed by, the first one wins if several tie
+    partial: bool,
+
+    /// Record the steps down to the node found
+    trail: bool,
+
+    /// The deepest data node passed by and the length of the unmatched remainder
+    pub(crate) deepest: Option<(T, usize)>,
+
+    /// The steps from the start node to the node found
+    pub(crate) steps: Vec<Step>,
+}
+
+impl<T> Track<T> {
+    /// Record the deepest data node passed by
+    #[inline]
+    pub(crate) fn partial() -> Self {
+        Self { partial: true, trail: false, deepest: None, steps: vec![] }
+    }
+
+    /// Record the steps down to the node found
+    #[inline]
+    pub(crate) fn trail() -> Self {
+        Self { partial: false, trail: true, deepest: None, steps: vec![] }
+    }
+
+    /// Pass by a data node with the length of the unmatched remainder
+    #[inline]
+    fn pass(&mut self, node: T, rest: usize) {
+        if self.partial && !matches!(self.deepest, Some((_, len)) if len <= rest) {
+            self.deepest = Some((node, rest));
+        }
+    }
+
+    /// Step down to a child
+    #[inline]
+    fn step(&mut self, step: Step) {
+        if self.trail {
+            self.steps.push(step);
+        }
+    }
+}
+
+/// Internal use only, the tree which the lookup walks through. RadixMap walks its nodes and
+/// FrozenRadixMap walks the records of its image, so that both share one lookup
+pub(crate) trait RadixWalk: Copy {
+    /// The special children in the order they are tried
+    type Specials: Iterator<Item = Self>;
+
+    /// The compiled rule of a special node, None for a plain one
+    fn rule(&self) -> Option<&RadixRule>;
+
+    /// The length of the fragment
+    fn size(&self) -> usize;
+
+    /// The longest part of the path matching the fragment, along with the normalized capture
+    fn longest<'u>(&self, path: &'u [u8], raw: bool) -> Option<(&'u [u8], Option<Bytes>)>;
+
+    /// The regular child which the byte leads to
+    fn regular(&self, byte: u8) -> Option<Self>;
+
+    /// The special children
+    fn specials(&self) -> Self::Specials;
+
+    /// Check if there are regular children
+    fn has_regular(&self) -> bool;
+
+    /// Check if there are special children
+    fn has_special(&self) -> bool;
+
+    /// Check if the node holds data
+    fn has_data(&self) -> bool;
+
+    /// Check if the node only stands for another data node
+    fn has_alias(&self) -> bool;
+
+    /// Push the default values of the alias and return the route of the data node
+    fn alias(&self, capture: &mut Params<'_>) -> Option<&[u8]>;
+
+    /// Find the deepest node matching the path, see RadixNode::lookup. The track records the
+    /// deepest data node passed by, or the steps down to the node found
+    fn walk<'u>(self, mut path: &'u [u8], data: bool, raw: bool, capture: &mut Params<'u>, enable: bool, mut track: Option<&mut Track<Self>>) -> Option<Self> {
+        let mut current = self;
+
+        loop {
+            // the param may stop early at the literals following it in the segment, they are
+            // tried shortest first as branches before the param takes the whole segment
+            let part = match raw || !current.has_regular() {
+                true => None,
+                false => current.rule().and_then(|rule| rule.segment(path)),
+            };
+
+            if let Some(part) = part {
+                let mark = capture.len();
+                let steps = track.as_deref().map_or(0, |track| track.steps.len());
+
+                for pos in 1..part.len() {
+                    let (next, share, norm) = match current.cut(path, pos) {
+                        Some(cut) => cut,
+                        None => continue,
+                    };
+
+                    if enable {
+                        current.collect(share, norm, capture);
+                    }
+
+                    if let Some(track) = track.as_deref_mut() {
+                        track.step(Step::Regular(path[pos]));
+                    }
+
+                    if let Some(find) = next.walk(&path[pos..], data, raw, capture, enable, track.as_deref_mut()) {
+                        return Some(find);
+                    }
+
+                    capture.truncate(mark);
+
+                    if let Some(track) = track.as_deref_mut() {
+                        track.steps.truncate(steps);
+                    }
+                }
+            }
+
+            // prefix must be part of the current node
+            let (share, norm) = current.longest(path, raw)?;
+            let equal = (!raw && current.rule().is_some()) || current.size() == share.len();
+            if share.len() != path.len() && !equal {
+                return None
+            }
+
+            if enable {
+                current.collect(share, norm, capture);
+            }
+
+            // trim the shared and continue lookup
+            path = &path[share.len()..];
+
+            if let Some(track) = track.as_deref_mut() {
+                if equal && (current.has_data() || current.has_alias()) {
+                    track.pass(current, path.len());
+                }
+            }
+
+            let (byte, found) = match path.first() {
+                Some(&byte) => (byte, current.regular(byte)),
+                None if data && (!equal || (!current.has_data() && !current.has_alias())) => (0, None), // data node must be an exact match
+                None => return Some(current),
+            };
+
+            // no need to recurse if there is no other choice
+            if !current.has_special() {
+                current = found?;
+
+                if let Some(track) = track.as_deref_mut() {
+                    track.step(Step::Regular(byte));
+                }
+
+                continue;
+            }
+
+            // backtrack to special nodes if the regular node dead-ends, rolling back the captures
+            let mark = capture.len();
+            let steps = track.as_deref().map_or(0, |track| track.steps.len());
+
+            let regular = found.map(|node| (Step::Regular(byte), node));
+            let special = current.specials().enumerate().map(|(index, node)| (Step::Special(index), node));
+
+            for (step, node) in regular.into_iter().chain(special) {
+                if let Some(track) = track.as_deref_mut() {
+                    track.step(step);
+                }
+
+                if let Some(find) = node.walk(path, data, raw, capture, enable, track.as_deref_mut()) {
+                    return Some(find);
+                }
+
+                capture.truncate(mark);
+
+                if let Some(track) = track.as_deref_mut() {
+                    track.steps.truncate(steps);
+                }
+            }
+
+            return None;
+        }
+    }
+
+    /// The regular child where the param stops early at the position of the path, which must
+    /// be a literal following it in the segment, along with the part taken by the param and its
+    /// normalized capture
+    fn cut(self, path: &[u8], pos: usize) -> Option<(Self, &[u8], Option<Bytes>)> {
+        let next = self.regular(path[pos])?;
+        let (share, norm) = self.longest(&path[..pos], false)?;
+        (share.len() == pos).then_some((next, share, norm))
+    }
+
+    /// Push the capture of the node, or the one normalized by the custom matcher
+    #[inline]
+    fn collect<'u>(self, share: &'u [u8], norm: Option<Bytes>, capture: &mut Params<'u>) {
+        let ident = match self.rule() {
+            Some(rule) if !rule.identity().is_empty() => rule.identity(),
+            _ => return,
+        };
+
+        match norm {
+            Some(data) => capture.push_owned(ident.clone(), data),
+            None => capture.push(ident.clone(), share),
+        }
+    }
+
+    /// Resolve the alias node to the data node it stands for from the root, see RadixNode::follow.
+    /// The track records the steps from the root to the data node if the alias is followed
+    fn follow(self, root: Self, capture: &mut Params<'_>, mut track: Option<&mut Track<Self>>) -> Option<Self> {
+        if self.has_data() {
+            return Some(self);
+        }
+
+        let link = self.alias(capture)?;
+        if let Some(track) = track.as_deref_mut() {
+            track.steps.clear();
+        }
+
+        root.walk(&RadixRule::unescape(link), true, true, &mut Params::new(), false, track).filter(|node| node.has_data())
+    }
+}
+
+impl<'n, V> RadixWalk for &'n RadixNode<V> {
+    type Specials = indexmap::map::Values<'n, Bytes, RadixNode<V>>;
+
+    #[inline]
+    fn rule(&self) -> Option<&RadixRule> {
+        self.rule.is_special().then_some(&self.rule)
+    }
+
+    #[inline]
+    fn size(&self) -> usize {
+        self.rule.origin().len()
+    }
+
+    #[inline]
+    fn longest<'u>(&self, path: &'u [u8], raw: bool) -> Option<(&'u [u8], Option<Bytes>)> {
+        self.rule.normalize(path, raw)
+    }
+
+    #[inline]
+    fn regular(&self, byte: u8) -> Option<Self> {
+        let node: &'n RadixNode<V> = self;
+        node.next.regular.get(node.next.index(byte))
+    }
+
+    #[inline]
+    fn specials(&self) -> Self::Specials {
+        let node: &'n RadixNode<V> = self;
+        node.next.special.values()
+    }
+
+    #[inline]
+    fn has_regular(&self) -> bool {
+        !self.next.regular.is_empty()
+    }
+
+    #[inline]
+    fn has_special(&self) -> bool {
+        !self.next.special.is_empty()
+    }
+
+    #[inline]
+    fn has_data(&self) -> bool {
+        !self.is_empty()
+    }
+
+    #[inline]
+    fn has_alias(&self) -> bool {
+        self.is_alias()
+    }
+
+    #[inline]
+    fn alias(&self, capture: &mut Params<'_>) -> Option<&[u8]> {
+        let alias = self.alias.as_ref()?;
+        for (name, data) in alias.defaults.iter() {
+            capture.push_owned(name.clone(), data.clone());
+        }
+
+        Some(&alias.link)
+    }
+}
+
+// -----------------------------------------------------------------------------
+
 /// Iterating order for radix tree
 ///
 /// # Example
@@ -665,7 +1455,7 @@ impl<'n, V> Iter<'n, V> {
         let cursor = cursor
             .and_then(|mut iter| iter.next())
             .and_then(|node| match !path.is_empty() {
-                true => node.lookup(path, data, false, &mut vec![], false),
+                true => node.lookup(path, data, false, &mut Params::new(), false),
                 false => None,
             });
 
@@ -905,7 +1695,7 @@ impl<'n, V> IterMut<'n, V> {
         let cursor = cursor
             .and_then(|mut iter| iter.next())
             .and_then(|node| match !path.is_empty() {
-                true => node.lookup_mut(path, data, false, &mut vec![], false),
+                true => node.lookup_mut(path, data, false, &mut Params::new(), false),
                 false => None,
             });
 
@@ -1186,4 +1976,90 @@ impl<'n, V> Iterator for ValuesMut<'n, V> {
     fn next(&mut self) -> Option<Self::Item> {
         self.iter.next().and_then(|node| node.data.as_mut())
     }
-}
\ No newline at end of file
+}
+
+// -----------------------------------------------------------------------------
+
+/// Internal use only, a pending node with the path, the length of the captures and the position
+/// where the param stops early
+type Visit<'n, 'u, V> = (&'n RadixNode<V>, &'u [u8], usize, Option<usize>);
+
+/// Iterator for all data nodes matching a path along with the captures
+#[derive(Clone)]
+pub struct Matches<'n, 'u, V> {
+    stack: Vec<Visit<'n, 'u, V>>,
+    capture: Params<'u>,
+}
+
+impl<'n, 'u, V> From<(&'n RadixNode<V>, &'u [u8])> for Matches<'n, 'u, V> {
+    #[inline]
+    fn from((start, path): (&'n RadixNode<V>, &'u [u8])) -> Self {
+        let mut capture = Params::new();
+        capture.bind(path);
+
+        Self { stack: vec![(start, path, 0, None)], capture }
+    }
+}
+
+impl<'n, 'u, V> Matches<'n, 'u, V> {
+    /// Internal use only, push the node and the positions where the param stops early, the
+    /// shortest one is popped first
+    fn visit(&mut self, node: &'n RadixNode<V>, path: &'u [u8], mark: usize) {
+        self.stack.push((node, path, mark, None));
+
+        let size = self.stack.len();
+        self.stack.extend(node.cuts(path).map(|pos| (node, path, mark, Some(pos))));
+        self.stack[size..].reverse();
+    }
+}
+
+impl<'n, 'u, V> Iterator for Matches<'n, 'u, V> {
+    type Item = (&'n RadixNode<V>, Params<'u>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((node, path, mark, cut)) = self.stack.pop() {
+            // discard the captures pushed by the previous branch
+            self.capture.truncate(mark);
+
+            // the param which stops early goes on with the literal only
+            if let Some(pos) = cut {
+                if let Some((next, share, norm)) = node.cut(path, pos) {
+                    node.collect(share, norm, &mut self.capture);
+                    self.stack.push((next, &path[pos..], self.capture.len(), None));
+                }
+
+                continue;
+            }
+
+            // the fragment of current node must be consumed entirely
+            let (share, norm) = match node.rule.normalize(path, false) {
+                Some(pair) => pair,
+                None => continue,
+            };
+
+            if !node.rule.is_special() && node.rule.origin().len() != share.len() {
+                continue;
+            }
+
+            node.collect(share, norm, &mut self.capture);
+
+            // push children in reverse so that they're popped in the order of lookup
+            let path = &path[share.len()..];
+            let mark = self.capture.len();
+
+            for next in node.next.special.values().rev() {
+                self.visit(next, path, mark);
+            }
+
+            if let Some(next) = path.first().and_then(|&byte| node.next.regular.get(node.next.index(byte))) {
+                self.stack.push((next, path, mark, None));
+            }
+
+            if path.is_empty() && (!node.is_empty() || node.is_alias()) {
+                return Some((node, self.capture.clone()));
+            }
+        }
+
+        None
+    }
+}
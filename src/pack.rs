@@ -7,10 +7,10 @@ use super::node::RadixNode;
 #[derive(Clone)]
 pub struct RadixPack<V> {
     /// The most common nodes, utilizing vector map to accelerate queries
-    pub regular: VecMap<RadixNode<V>>,
+    pub(crate) regular: VecMap<RadixNode<V>>,
 
     /// Nodes which need to be checked one by one to determine if they match
-    pub special: IndexMap<Bytes, RadixNode<V>>,
+    pub(crate) special: IndexMap<Bytes, RadixNode<V>>,
 }
 
 impl<V> RadixPack<V> {
@@ -27,6 +27,7 @@ impl<V> RadixPack<V> {
     /// ```
     /// use radixmap::{pack::RadixPack, rule::RadixRule, RadixResult};
     ///
+    /// # #[cfg(feature = "regex")]
     /// fn main() -> RadixResult<()> {
     ///     let mut pack = RadixPack::<()>::default();
     ///     pack.insert(RadixRule::try_from("/api")?)?;
@@ -39,9 +40,11 @@ impl<V> RadixPack<V> {
     ///
     ///     Ok(())
     /// }
+    /// # #[cfg(not(feature = "regex"))]
+    /// # fn main() {}
     /// ```
     #[inline]
-    pub fn iter(&self) -> Iter<V> {
+    pub fn iter(&self) -> Iter<'_, V> {
         Iter::from(self)
     }
 
@@ -52,6 +55,7 @@ impl<V> RadixPack<V> {
     /// ```
     /// use radixmap::{pack::RadixPack, rule::RadixRule, RadixResult};
     ///
+    /// # #[cfg(feature = "regex")]
     /// fn main() -> RadixResult<()> {
     ///     let mut pack = RadixPack::<()>::default();
     ///     pack.insert(RadixRule::try_from("/api")?)?;
@@ -69,9 +73,11 @@ impl<V> RadixPack<V> {
     ///
     ///     Ok(())
     /// }
+    /// # #[cfg(not(feature = "regex"))]
+    /// # fn main() {}
     /// ```
     #[inline]
-    pub fn iter_mut(&mut self) -> IterMut<V> {
+    pub fn iter_mut(&mut self) -> IterMut<'_, V> {
         IterMut::from(self)
     }
 
@@ -82,6 +88,7 @@ impl<V> RadixPack<V> {
     /// ```
     /// use radixmap::{pack::RadixPack, rule::RadixRule, RadixResult};
     ///
+    /// # #[cfg(feature = "regex")]
     /// fn main() -> RadixResult<()> {
     ///     let mut pack = RadixPack::<()>::default();
     ///
@@ -90,33 +97,31 @@ impl<V> RadixPack<V> {
     ///     assert_eq!(pack.insert(RadixRule::from_param(":id")?)?.rule, b":id");
     ///     assert_eq!(pack.insert(RadixRule::from_regex("{[0-9]+}")?)?.rule, b"{[0-9]+}");
     ///
-    ///     assert_eq!(pack.regular.len(), 1);
-    ///     assert_eq!(pack.special.len(), 2);
+    ///     assert_eq!(pack.iter().count(), 3);
     ///
     ///     // inserting duplicate nodes has no effect
     ///     assert_eq!(pack.insert(RadixRule::from_plain("/api")?)?.rule, b"/api");
     ///     assert_eq!(pack.insert(RadixRule::from_param(":id")?)?.rule, b":id");
     ///     assert_eq!(pack.insert(RadixRule::from_regex("{[0-9]+}")?)?.rule, b"{[0-9]+}");
     ///
-    ///     assert_eq!(pack.regular.len(), 1);
-    ///     assert_eq!(pack.special.len(), 2);
+    ///     assert_eq!(pack.iter().count(), 3);
     ///
     ///     Ok(())
     /// }
+    /// # #[cfg(not(feature = "regex"))]
+    /// # fn main() {}
     /// ```
     pub fn insert(&mut self, rule: RadixRule) -> RadixResult<&mut RadixNode<V>> {
         // special nodes inserted directly into map
-        let frag = rule.origin();
-        if !matches!(rule, RadixRule::Plain { .. }) {
-            return match self.special.contains_key(frag) {
-                true => Ok(&mut self.special[frag]),
-                false => Ok(self.special.entry(frag.clone()).or_insert(RadixNode::from(rule)))
-            };
+        if rule.is_special() {
+            return Ok(self.insert_special(rule, None));
         }
 
+        let frag = rule.origin();
+
         // Use vector map to find regular node. Since tree nodes
         // share prefixes, indexing only the first byte is sufficient
-        let first = *frag.first().ok_or(RadixError::PathEmpty)? as usize;
+        let first = Self::key(&rule)?;
 
         // insert regular node if no shared prefix
         if !self.regular.contains_key(first) {
@@ -138,17 +143,221 @@ impl<V> RadixPack<V> {
         // divide the node into two parts
         if !equal {
             let node = found.divide(share.len())?;
-            let byte = node.rule.origin()[0] as usize;
+            let byte = Self::key(&node.rule)?;
             found.next.regular.insert(byte, node);
         }
 
         // insert the remaining path if found
         match frag.len() != share.len() {
-            true => found.next.insert(RadixRule::try_from(frag.slice(share.len()..))?),
+            true => found.next.insert(rule.clone().divide(share.len())?),
             false => Ok(found),
         }
     }
 
+    /// Insert new node and assign it an explicit priority. Special nodes are tried in
+    /// descending priority, nodes with the same priority are ordered by their rules.
+    /// The priority of a regular node has no effect since it's always tried first
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{pack::RadixPack, rule::RadixRule, RadixResult};
+    ///
+    /// # #[cfg(all(feature = "glob", feature = "regex"))]
+    /// fn main() -> RadixResult<()> {
+    ///     let mut pack = RadixPack::<()>::default();
+    ///     pack.insert(RadixRule::from_glob("*")?)?;
+    ///     pack.insert(RadixRule::from_param(":id")?)?;
+    ///     pack.insert(RadixRule::from_regex(r"{id:\d+}")?)?;
+    ///
+    ///     // sorted by the rules by default
+    ///     assert_eq!(pack.iter().map(|node| node.rule.origin()).collect::<Vec<_>>(), [r"{id:\d+}", ":id", "*"]);
+    ///
+    ///     // raise the priority of the glob node
+    ///     assert_eq!(pack.insert_with_priority(RadixRule::from_glob("*")?, 1)?.priority, 1);
+    ///     assert_eq!(pack.iter().map(|node| node.rule.origin()).collect::<Vec<_>>(), ["*", r"{id:\d+}", ":id"]);
+    ///
+    ///     Ok(())
+    /// }
+    /// # #[cfg(not(all(feature = "glob", feature = "regex")))]
+    /// # fn main() {}
+    /// ```
+    pub fn insert_with_priority(&mut self, rule: RadixRule, priority: i32) -> RadixResult<&mut RadixNode<V>> {
+        match rule.is_special() {
+            true => Ok(self.insert_special(rule, Some(priority))),
+            false => self.insert(rule),
+        }
+    }
+
+    /// Find the special sibling which shadows the rule inserted with the priority, or which is
+    /// shadowed by it. Since the lookup backtracks, two siblings only shadow each other if they
+    /// match the same segments under another name and the rest of the route leads to a data node
+    /// of the existing one too, or if a catch-all tried before the other one ends its route
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{pack::RadixPack, rule::RadixRule, RadixResult};
+    ///
+    /// # #[cfg(all(feature = "glob", feature = "regex"))]
+    /// fn main() -> RadixResult<()> {
+    ///     let mut pack = RadixPack::<()>::default();
+    ///     pack.insert(RadixRule::from_param(":id")?)?.data = Some(());
+    ///     pack.insert(RadixRule::from_glob("*")?)?.data = Some(());
+    ///
+    ///     assert_eq!(pack.conflict(&RadixRule::from_param(":id")?, &[], None).map(|node| &node.rule), None);
+    ///     assert_eq!(pack.conflict(&RadixRule::from_param(":name")?, &[], None).map(|node| &node.rule), Some(&RadixRule::from_param(":id")?));
+    ///     assert_eq!(pack.conflict(&RadixRule::from_glob("{*rest}")?, &[], None).map(|node| &node.rule), Some(&RadixRule::from_glob("*")?));
+    ///     assert_eq!(pack.conflict(&RadixRule::from_regex(r"{id:\d+}")?, &[], None).map(|node| &node.rule), None);
+    ///
+    ///     // the continuation differs from the existing one, the lookup backtracks to it
+    ///     assert_eq!(pack.conflict(&RadixRule::from_param(":name")?, &[RadixRule::from_plain("/posts")?], None).map(|node| &node.rule), None);
+    ///
+    ///     // the catch-all tried before the param shadows it
+    ///     assert_eq!(pack.conflict(&RadixRule::from_glob("*")?, &[], Some(1)).map(|node| &node.rule), Some(&RadixRule::from_param(":id")?));
+    ///     assert_eq!(pack.conflict(&RadixRule::from_regex(r"{id:\d+}")?, &[], Some(-1)).map(|node| &node.rule), Some(&RadixRule::from_glob("*")?));
+    ///
+    ///     Ok(())
+    /// }
+    /// # #[cfg(not(all(feature = "glob", feature = "regex")))]
+    /// # fn main() {}
+    /// ```
+    pub fn conflict(&self, rule: &RadixRule, rest: &[RadixRule], priority: Option<i32>) -> Option<&RadixNode<V>> {
+        if rule.is_plain() {
+            return None;
+        }
+
+        let frag = rule.origin();
+        let priority = priority.or_else(|| self.special.get(frag).map(|node| node.priority)).unwrap_or(0);
+
+        self.special.values().filter(|node| node.rule.origin() != frag).find(|node| {
+            let ahead = node.priority.cmp(&priority).then_with(|| rule.cmp(&node.rule)).is_lt();
+
+            match rule.is_equivalent(&node.rule) {
+                true => Self::overlap(node, rest),
+                false => match ahead {
+                    true => rule.is_catch_all() && rest.is_empty() && (node.data.is_some() || !node.next.is_empty()),
+                    false => node.rule.is_catch_all() && node.data.is_some(),
+                }
+            }
+        })
+    }
+
+    /// Internal use only, check if the rest of a route leads to a data node under the node via
+    /// the same or equivalent rules, so the lookup never reaches the new one
+    fn overlap(node: &RadixNode<V>, rest: &[RadixRule]) -> bool {
+        let (next, rest) = match rest.split_first() {
+            Some(pair) => pair,
+            None => return node.data.is_some(),
+        };
+
+        if next.is_special() {
+            return node.next.special.values().any(|item| {
+                (item.rule.origin() == next.origin() || item.rule.is_equivalent(next)) && Self::overlap(item, rest)
+            });
+        }
+
+        let mut text = next.origin().as_ref();
+        let mut slot = node;
+
+        while !text.is_empty() {
+            let node = match slot.next.regular.get(slot.next.index(text[0])) {
+                Some(node) => node,
+                None => return false,
+            };
+
+            let share = node.rule.longest(text, false).map_or(0, |share| share.len());
+            if share != node.rule.origin().len() {
+                return false;
+            }
+
+            text = &text[share..];
+            slot = node;
+        }
+
+        Self::overlap(slot, rest)
+    }
+
+    /// Internal use only, insert the special node at its sorted position
+    fn insert_special(&mut self, rule: RadixRule, priority: Option<i32>) -> &mut RadixNode<V> {
+        let frag = rule.origin().clone();
+        let mut index = match self.special.get_index_of(&frag) {
+            Some(index) => index,
+            None => {
+                let node = RadixNode::from(rule);
+                let index = self.special.values().position(|item| Self::order(&node, item).is_lt()).unwrap_or(self.special.len());
+                self.special.shift_insert(index, frag.clone(), node);
+                index
+            }
+        };
+
+        // changing the priority may break the order
+        if let Some(priority) = priority {
+            if self.special[index].priority != priority {
+                self.special[index].priority = priority;
+                self.special.sort_by(|_, a, _, b| Self::order(a, b));
+                index = self.special.get_index_of(&frag).unwrap_or(index);
+            }
+        }
+
+        &mut self.special[index]
+    }
+
+    /// The key of the regular node which the byte leads to. Case-insensitive nodes are keyed
+    /// by the lowercase byte, so an uppercase byte falls back to it
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{pack::RadixPack, rule::RadixRule, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut pack = RadixPack::<()>::default();
+    ///     pack.insert(RadixRule::from_plain("Api")?.ignore_case()?)?;
+    ///     pack.insert(RadixRule::from_plain("Blog")?)?;
+    ///
+    ///     assert_eq!(pack.index(b'A'), b'a' as usize);
+    ///     assert_eq!(pack.index(b'B'), b'B' as usize);
+    ///     assert_eq!(pack.index(b'c'), b'c' as usize);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn index(&self, byte: u8) -> usize {
+        match self.regular.contains_key(byte as usize) || !byte.is_ascii_uppercase() {
+            true => byte as usize,
+            false => byte.to_ascii_lowercase() as usize,
+        }
+    }
+
+    /// Internal use only, the key of the regular node
+    #[inline]
+    fn key(rule: &RadixRule) -> RadixResult<usize> {
+        let byte = *rule.origin().first().ok_or(RadixError::PathEmpty)?;
+
+        match rule.is_icase() {
+            true => Ok(byte.to_ascii_lowercase() as usize),
+            false => Ok(byte as usize),
+        }
+    }
+
+    /// Internal use only, higher priority first, then the more specific rule
+    #[inline]
+    fn order(a: &RadixNode<V>, b: &RadixNode<V>) -> Ordering {
+        b.priority.cmp(&a.priority).then_with(|| a.rule.cmp(&b.rule))
+    }
+
+    /// Release the unused capacity of the group and its descendants
+    pub fn shrink_to_fit(&mut self) {
+        self.regular.shrink_to_fit();
+        self.special.shrink_to_fit();
+
+        for node in self.iter_mut() {
+            node.next.shrink_to_fit();
+        }
+    }
+
     /// Clear the nodes and preserve its capacity
     ///
     /// # Examples
@@ -156,14 +365,14 @@ impl<V> RadixPack<V> {
     /// ```
     /// use radixmap::{pack::RadixPack, rule::RadixRule, RadixResult};
     ///
+    /// # #[cfg(feature = "regex")]
     /// fn main() -> RadixResult<()> {
     ///     let mut pack = RadixPack::<()>::default();
     ///     pack.insert(RadixRule::from_plain("/api")?)?;
     ///     pack.insert(RadixRule::from_param(":id")?)?;
     ///     pack.insert(RadixRule::from_regex("{}")?)?;
     ///
-    ///     assert_eq!(pack.regular.len(), 1);
-    ///     assert_eq!(pack.special.len(), 2);
+    ///     assert_eq!(pack.iter().count(), 3);
     ///
     ///     pack.clear();
     ///
@@ -171,6 +380,8 @@ impl<V> RadixPack<V> {
     ///
     ///     Ok(())
     /// }
+    /// # #[cfg(not(feature = "regex"))]
+    /// # fn main() {}
     /// ```
     #[inline]
     pub fn clear(&mut self) {
@@ -183,7 +394,7 @@ impl<V> RadixPack<V> {
 impl<V> Default for RadixPack<V> {
     #[inline]
     fn default() -> Self {
-        Self { regular: VecMap::new(), special: IndexMap::new() }
+        Self { regular: VecMap::new(), special: IndexMap::default() }
     }
 }
 
@@ -193,7 +404,7 @@ impl<V> Default for RadixPack<V> {
 #[derive(Default, Clone)]
 pub struct Iter<'n, V> {
     onetime: Option<&'n RadixNode<V>>,
-    regular: Option<vec_map::Values<'n, RadixNode<V>>>,
+    regular: Option<core::iter::Flatten<core::slice::Iter<'n, Option<RadixNode<V>>>>>,
     special: indexmap::map::Values<'n, Bytes, RadixNode<V>>,
 }
 
@@ -236,7 +447,7 @@ impl<'n, V> Iterator for Iter<'n, V> {
 #[derive(Default)]
 pub struct IterMut<'n, V> {
     onetime: Option<&'n mut RadixNode<V>>,
-    regular: Option<vec_map::ValuesMut<'n, RadixNode<V>>>,
+    regular: Option<core::iter::Flatten<core::slice::IterMut<'n, Option<RadixNode<V>>>>>,
     special: indexmap::map::ValuesMut<'n, Bytes, RadixNode<V>>,
 }
 
@@ -271,4 +482,133 @@ impl<'n, V> Iterator for IterMut<'n, V> {
 
         self.special.next()
     }
-}
\ No newline at end of file
+}
+
+// -----------------------------------------------------------------------------
+
+/// Vector map keyed by small integers, the regular nodes are indexed by their first byte
+#[derive(Clone)]
+pub(crate) struct VecMap<V> {
+    slot: Vec<Option<V>>,
+    size: usize,
+}
+
+impl<V> VecMap<V> {
+    /// Create an empty map
+    #[inline]
+    pub(crate) fn new() -> Self {
+        Self { slot: Vec::new(), size: 0 }
+    }
+
+    /// The number of values
+    #[inline]
+    pub(crate) fn len(&self) -> usize {
+        self.size
+    }
+
+    /// Check if the map has no values
+    #[inline]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// Check if the key holds a value
+    #[inline]
+    pub(crate) fn contains_key(&self, key: usize) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Get the value of the key
+    #[inline]
+    pub(crate) fn get(&self, key: usize) -> Option<&V> {
+        self.slot.get(key).and_then(|data| data.as_ref())
+    }
+
+    /// Get the mutable value of the key
+    #[inline]
+    pub(crate) fn get_mut(&mut self, key: usize) -> Option<&mut V> {
+        self.slot.get_mut(key).and_then(|data| data.as_mut())
+    }
+
+    /// Insert the value and return the old one if exist
+    pub(crate) fn insert(&mut self, key: usize, data: V) -> Option<V> {
+        if key >= self.slot.len() {
+            self.slot.resize_with(key + 1, || None);
+        }
+
+        let prev = self.slot[key].replace(data);
+        if prev.is_none() {
+            self.size += 1;
+        }
+
+        prev
+    }
+
+    /// Remove the value of the key
+    pub(crate) fn remove(&mut self, key: usize) -> Option<V> {
+        let prev = self.slot.get_mut(key)?.take();
+        if prev.is_some() {
+            self.size -= 1;
+        }
+
+        prev
+    }
+
+    /// Retain the values which the predicate returns true for
+    pub(crate) fn retain<F: FnMut(usize, &mut V) -> bool>(&mut self, mut func: F) {
+        for (key, slot) in self.slot.iter_mut().enumerate() {
+            if slot.as_mut().is_some_and(|data| !func(key, data)) {
+                *slot = None;
+                self.size -= 1;
+            }
+        }
+    }
+
+    /// Take all the key-value pairs out in the key order
+    pub(crate) fn drain(&mut self) -> impl Iterator<Item = (usize, V)> + '_ {
+        self.size = 0;
+        self.slot.drain(..).enumerate().filter_map(|(key, data)| data.map(|data| (key, data)))
+    }
+
+    /// Iterate the key-value pairs in the key order
+    #[inline]
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (usize, &V)> + '_ {
+        self.slot.iter().enumerate().filter_map(|(key, data)| data.as_ref().map(|data| (key, data)))
+    }
+
+    /// Iterate the values in the key order
+    #[inline]
+    pub(crate) fn values(&self) -> core::iter::Flatten<core::slice::Iter<'_, Option<V>>> {
+        self.slot.iter().flatten()
+    }
+
+    /// Iterate the mutable values in the key order
+    #[inline]
+    pub(crate) fn values_mut(&mut self) -> core::iter::Flatten<core::slice::IterMut<'_, Option<V>>> {
+        self.slot.iter_mut().flatten()
+    }
+
+    /// Release the unused capacity, including the empty slots at the end
+    pub(crate) fn shrink_to_fit(&mut self) {
+        while self.slot.last().is_some_and(|data| data.is_none()) {
+            self.slot.pop();
+        }
+
+        self.slot.shrink_to_fit();
+    }
+
+    /// Clear the values but preserve its capacity
+    #[inline]
+    pub(crate) fn clear(&mut self) {
+        self.slot.clear();
+        self.size = 0;
+    }
+}
+
+/// Default Trait
+impl<V> Default for VecMap<V> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,442 @@
+//! Params represents the captures of a lookup
+use super::defs::*;
+use core::str::FromStr;
+
+/// The named captures of a path, in the order they appear in the route.
+/// Names are shared with the tree without copying the underlying bytes
+pub struct Params<'u> {
+    /// The path which the captures are positions of
+    path: &'u [u8],
+
+    /// The captures, owned or kept in a caller's buffer
+    items: Items<'u>,
+}
+
+/// The reusable buffer of the captures, see RadixMap::capture_into. It stores the positions
+/// of the captures rather than borrowing the path, so one allocation serves every lookup
+///
+/// # Examples
+///
+/// ```
+/// use radixmap::{params::ParamsBuf, RadixMap, RadixResult};
+///
+/// fn main() -> RadixResult<()> {
+///     let map = RadixMap::try_from([("/user/:id/:tab", "user")])?;
+///     let mut buff = ParamsBuf::new();
+///
+///     for id in [12345, 67890] {
+///         let path = format!("/user/{id}/posts");
+///         let (data, capt) = map.capture_into(path.as_bytes(), &mut buff);
+///
+///         assert_eq!(data, Some(&"user"));
+///         assert_eq!(capt.parse::<u32>("id")?, id);
+///         assert_eq!(capt.get("tab"), Some(b"posts".as_ref()));
+///     }
+///
+///     assert!(buff.capacity() >= 2);
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Default, Clone)]
+pub struct ParamsBuf {
+    items: Vec<(Bytes, Value)>,
+}
+
+/// Internal use only, the captures owned by the params or borrowed from a buffer
+enum Items<'u> {
+    Owned(Vec<(Bytes, Value)>),
+    Buffer(&'u mut Vec<(Bytes, Value)>),
+}
+
+/// Internal use only, a captured value as a range of the path or owned by itself
+#[derive(Clone)]
+enum Value {
+    Span(usize, usize),
+    Owned(Bytes),
+}
+
+impl Value {
+    #[inline]
+    fn as_bytes<'a>(&'a self, path: &'a [u8]) -> &'a [u8] {
+        match self {
+            Value::Span(from, to) => &path[*from..*to],
+            Value::Owned(data) => data,
+        }
+    }
+}
+
+impl ParamsBuf {
+    /// Create an empty buffer
+    #[inline]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// The number of the captures the buffer holds without reallocating
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.items.capacity()
+    }
+
+    /// Internal use only, clear the buffer and collect the captures of the path into it
+    #[inline]
+    pub(crate) fn params<'u>(&'u mut self, path: &'u [u8]) -> Params<'u> {
+        self.items.clear();
+        Params { path, items: Items::Buffer(&mut self.items) }
+    }
+}
+
+impl<'u> Params<'u> {
+    /// Construct a new empty params
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::params::Params;
+    ///
+    /// assert!(Params::new().is_empty());
+    /// ```
+    #[inline]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// The number of the captures
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{RadixMap, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let map = RadixMap::try_from([("/:year/:month", ())])?;
+    ///
+    ///     assert_eq!(map.capture(b"/2024/05").1.len(), 2);
+    ///     assert_eq!(map.capture(b"/2024").1.len(), 0);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.items().len()
+    }
+
+    /// Check if there are no captures
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.items().is_empty()
+    }
+
+    /// Get the first capture with the name
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{RadixMap, RadixResult};
+    ///
+    /// # #[cfg(feature = "regex")]
+    /// fn main() -> RadixResult<()> {
+    ///     let map = RadixMap::try_from([(r"/user/:id/{tab:[a-z]+}", ())])?;
+    ///     let (_, params) = map.capture(b"/user/12345/posts");
+    ///
+    ///     assert_eq!(params.get("id"), Some(b"12345".as_ref()));
+    ///     assert_eq!(params.get("tab"), Some(b"posts".as_ref()));
+    ///     assert_eq!(params.get("name"), None);
+    ///
+    ///     Ok(())
+    /// }
+    /// # #[cfg(not(feature = "regex"))]
+    /// # fn main() {}
+    /// ```
+    #[inline]
+    pub fn get(&self, name: impl AsRef<[u8]>) -> Option<&[u8]> {
+        self.get_all(name).next()
+    }
+
+    /// Get all the captures with the name
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{RadixMap, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let map = RadixMap::try_from([("/blog/:date/:author/:date", ())])?;
+    ///     let (_, params) = map.capture(b"/blog/2024-04-10/chensoft/2024-05-01");
+    ///
+    ///     assert_eq!(params.get_all("date").collect::<Vec<_>>(), [b"2024-04-10", b"2024-05-01"]);
+    ///     assert_eq!(params.get_all("author").collect::<Vec<_>>(), [b"chensoft"]);
+    ///     assert_eq!(params.get_all("name").count(), 0);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn get_all(&self, name: impl AsRef<[u8]>) -> impl Iterator<Item = &[u8]> {
+        self.iter().filter(move |item| item.0 == name.as_ref()).map(|item| item.1)
+    }
+
+    /// Get the capture by position
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use radixmap::{RadixMap, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let map = RadixMap::try_from([("/:year/:month", ())])?;
+    ///     let (_, params) = map.capture(b"/2024/05");
+    ///
+    ///     assert_eq!(params.nth(0), Some((&Bytes::from("year"), b"2024".as_ref())));
+    ///     assert_eq!(params.nth(1), Some((&Bytes::from("month"), b"05".as_ref())));
+    ///     assert_eq!(params.nth(2), None);
+    ///
+    ///     assert_eq!(&params[1], b"05");
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn nth(&self, index: usize) -> Option<(&Bytes, &[u8])> {
+        self.items().get(index).map(|(name, data)| (name, data.as_bytes(self.path)))
+    }
+
+    /// Parse the first capture with the name
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{RadixMap, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let map = RadixMap::try_from([("/user/:id", ())])?;
+    ///
+    ///     assert_eq!(map.capture(b"/user/12345").1.parse::<u32>("id")?, 12345);
+    ///     assert!(map.capture(b"/user/12345").1.parse::<u32>("name").is_err());
+    ///     assert!(map.capture(b"/user/abcde").1.parse::<u32>("id").is_err());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn parse<T: FromStr>(&self, name: impl AsRef<[u8]>) -> RadixResult<T> {
+        let data = self.get(name).ok_or(RadixError::ParamNotFound)?;
+        core::str::from_utf8(data)?.parse().map_err(|_| RadixError::ParamInvalid)
+    }
+
+    /// Iterate the name-value pairs
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use radixmap::{RadixMap, RadixResult};
+    ///
+    /// # #[cfg(feature = "glob")]
+    /// fn main() -> RadixResult<()> {
+    ///     let map = RadixMap::try_from([("/:year/*", ())])?;
+    ///     let (_, params) = map.capture(b"/2024/05/01");
+    ///
+    ///     let mut iter = params.iter();
+    ///     assert_eq!(iter.next(), Some((&Bytes::from("year"), b"2024".as_ref())));
+    ///     assert_eq!(iter.next(), Some((&Bytes::from("*"), b"05/01".as_ref())));
+    ///     assert_eq!(iter.next(), None);
+    ///
+    ///     Ok(())
+    /// }
+    /// # #[cfg(not(feature = "glob"))]
+    /// # fn main() {}
+    /// ```
+    #[inline]
+    pub fn iter(&self) -> Iter<'_> {
+        Iter { path: self.path, iter: self.items().iter() }
+    }
+
+    /// Clear the captures and preserve its capacity
+    #[inline]
+    pub fn clear(&mut self) {
+        self.items_mut().clear();
+    }
+
+    /// Convert the captures into owned slices of the path they were borrowed from
+    ///
+    /// # Panics
+    ///
+    /// Panics if any capture is not a part of the path
+    pub fn into_owned(self, path: &Bytes) -> Params<'static> {
+        let items = self.items().iter().map(|(name, data)| {
+            let data = match data {
+                Value::Span(..) => path.slice_ref(data.as_bytes(self.path)),
+                Value::Owned(data) => data.clone(),
+            };
+            (name.clone(), Value::Owned(data))
+        });
+
+        Params { path: &[], items: Items::Owned(items.collect()) }
+    }
+
+    /// Internal use only, collect the captures as positions of the path from now on. The
+    /// captures of another path are copied, the subslices of the current path change nothing
+    #[inline]
+    pub(crate) fn bind(&mut self, path: &'u [u8]) {
+        if Self::within(self.path, path) {
+            return;
+        }
+
+        let prev = self.path;
+        for (_, data) in self.items_mut().iter_mut() {
+            if let Value::Span(..) = data {
+                *data = Value::Owned(Bytes::copy_from_slice(data.as_bytes(prev)));
+            }
+        }
+
+        self.path = path;
+    }
+
+    /// Internal use only, append a capture which is a part of the path, others are copied
+    #[inline]
+    pub(crate) fn push(&mut self, name: Bytes, data: &'u [u8]) {
+        let data = match Self::within(self.path, data) {
+            true => {
+                let from = data.as_ptr() as usize - self.path.as_ptr() as usize;
+                Value::Span(from, from + data.len())
+            }
+            false => Value::Owned(Bytes::copy_from_slice(data)),
+        };
+
+        self.items_mut().push((name, data));
+    }
+
+    /// Internal use only, append a capture owned by itself, e.g. the default value of an absent param
+    #[inline]
+    pub(crate) fn push_owned(&mut self, name: Bytes, data: Bytes) {
+        self.items_mut().push((name, Value::Owned(data)));
+    }
+
+    /// Internal use only, shorten the captures to roll back an abandoned branch
+    #[inline]
+    pub(crate) fn truncate(&mut self, len: usize) {
+        self.items_mut().truncate(len);
+    }
+
+    /// Internal use only, check if the slice lies in the path
+    #[inline]
+    fn within(path: &[u8], data: &[u8]) -> bool {
+        let (base, from) = (path.as_ptr() as usize, data.as_ptr() as usize);
+        from >= base && from + data.len() <= base + path.len()
+    }
+
+    /// Internal use only, the captures wherever they are kept
+    #[inline]
+    fn items(&self) -> &Vec<(Bytes, Value)> {
+        match &self.items {
+            Items::Owned(items) => items,
+            Items::Buffer(items) => items,
+        }
+    }
+
+    /// Internal use only, same as items
+    #[inline]
+    fn items_mut(&mut self) -> &mut Vec<(Bytes, Value)> {
+        match &mut self.items {
+            Items::Owned(items) => items,
+            Items::Buffer(items) => items,
+        }
+    }
+}
+
+/// Default trait
+impl Default for Params<'_> {
+    #[inline]
+    fn default() -> Self {
+        Self { path: &[], items: Items::Owned(vec![]) }
+    }
+}
+
+/// Clone trait, the clone owns its captures even if they are kept in a buffer
+impl<'u> Clone for Params<'u> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self { path: self.path, items: Items::Owned(self.items().clone()) }
+    }
+}
+
+/// Index by position
+impl Index<usize> for Params<'_> {
+    type Output = [u8];
+
+    #[inline]
+    fn index(&self, index: usize) -> &Self::Output {
+        self.items()[index].1.as_bytes(self.path)
+    }
+}
+
+/// Construct from name-value pairs
+impl<'u> From<Vec<(Bytes, &'u [u8])>> for Params<'u> {
+    #[inline]
+    fn from(value: Vec<(Bytes, &'u [u8])>) -> Self {
+        let mut params = Self::new();
+
+        for (name, data) in value {
+            params.push(name, data);
+        }
+
+        params
+    }
+}
+
+/// Debug trait
+impl Debug for Params<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// == & !=
+impl Eq for Params<'_> {}
+
+/// == & !=
+impl PartialEq for Params<'_> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+/// == & !=
+impl PartialEq<Vec<(Bytes, &[u8])>> for Params<'_> {
+    #[inline]
+    fn eq(&self, other: &Vec<(Bytes, &[u8])>) -> bool {
+        self.iter().eq(other.iter().map(|(name, data)| (name, *data)))
+    }
+}
+
+impl<'p, 'u> IntoIterator for &'p Params<'u> {
+    type Item = (&'p Bytes, &'p [u8]);
+    type IntoIter = Iter<'p>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+// -----------------------------------------------------------------------------
+
+/// Iterator for params
+#[derive(Clone)]
+pub struct Iter<'p> {
+    path: &'p [u8],
+    iter: core::slice::Iter<'p, (Bytes, Value)>,
+}
+
+impl<'p> Iterator for Iter<'p> {
+    type Item = (&'p Bytes, &'p [u8]);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(name, data)| (name, data.as_bytes(self.path)))
+    }
+}
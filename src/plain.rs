@@ -0,0 +1,861 @@
+//! Plain-only map and set implementation
+//!
+//! Keys are stored verbatim, signs such as `:`, `*`, `{` or `\` are ordinary bytes, and the lookup
+//! walks the regular children only, with no special scan and no capture bookkeeping
+use super::defs::*;
+use super::map::{self, Iter, IterMut, Keys, Values, ValuesMut};
+use super::node::{self, RadixNode};
+use super::rule::RadixRule;
+
+/// Radix map which treats every key as plain bytes
+pub struct PlainRadixMap<V> {
+    /// The root node, always empty
+    root: RadixNode<V>,
+
+    /// The number of data nodes
+    size: usize,
+}
+
+impl<V> PlainRadixMap<V> {
+    /// For consistency with the standard library, we provide this fn to create an empty map
+    #[inline]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// The data nodes' count, the empty nodes are ignored
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{PlainRadixMap, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut map = PlainRadixMap::new();
+    ///     assert_eq!(map.len(), 0);
+    ///
+    ///     map.insert("/api", ())?;
+    ///     assert_eq!(map.len(), 1);
+    ///
+    ///     map.insert("/api/:id", ())?;
+    ///     assert_eq!(map.len(), 2);
+    ///
+    ///     map.insert("/api/:id", ())?;
+    ///     assert_eq!(map.len(), 2);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    /// Check if the tree has no data nodes
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{PlainRadixMap, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut map = PlainRadixMap::new();
+    ///     assert_eq!(map.is_empty(), true);
+    ///
+    ///     map.insert("/api", ())?;
+    ///     assert_eq!(map.is_empty(), false);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Retrieve the corresponding data, the path is compared byte by byte
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{PlainRadixMap, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut map = PlainRadixMap::new();
+    ///     map.insert("/api/:id", "id")?;
+    ///     map.insert("/api/*", "star")?;
+    ///     map.insert("/api/{[0-9]+}", "brace")?;
+    ///
+    ///     assert_eq!(map.get(b"/api/:id"), Some(&"id"));
+    ///     assert_eq!(map.get(b"/api/*"), Some(&"star"));
+    ///     assert_eq!(map.get(b"/api/{[0-9]+}"), Some(&"brace"));
+    ///     assert_eq!(map.get(b"/api/12345"), None);
+    ///     assert_eq!(map.get(b"/api"), None);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn get(&self, path: &[u8]) -> Option<&V> {
+        self.lookup(path).and_then(|node| node.data.as_ref())
+    }
+
+    /// Retrieve the corresponding mutable data
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{PlainRadixMap, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut map = PlainRadixMap::new();
+    ///     map.insert("/api/v1", "v1")?;
+    ///
+    ///     if let Some(data) = map.get_mut(b"/api/v1") {
+    ///         *data = "v2";
+    ///     }
+    ///
+    ///     assert_eq!(map.get(b"/api/v1"), Some(&"v2"));
+    ///     assert_eq!(map.get_mut(b"/api"), None);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn get_mut(&mut self, path: &[u8]) -> Option<&mut V> {
+        self.lookup_mut(path).and_then(|node| node.data.as_mut())
+    }
+
+    /// Find the longest key which is a prefix of the path, along with the unmatched remainder
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use radixmap::{PlainRadixMap, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut map = PlainRadixMap::new();
+    ///     map.insert("/", "root")?;
+    ///     map.insert("/static", "static")?;
+    ///     map.insert("/static/css", "css")?;
+    ///
+    ///     assert_eq!(map.longest_prefix(b"/static/css/app.css"), Some((&Bytes::from("/static/css"), &"css", b"/app.css".as_ref())));
+    ///     assert_eq!(map.longest_prefix(b"/static/js"), Some((&Bytes::from("/static"), &"static", b"/js".as_ref())));
+    ///     assert_eq!(map.longest_prefix(b"/stat"), Some((&Bytes::from("/"), &"root", b"stat".as_ref())));
+    ///     assert_eq!(map.longest_prefix(b"static"), None);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn longest_prefix<'u>(&self, path: &'u [u8]) -> Option<(&Bytes, &V, &'u [u8])> {
+        let mut best = None;
+        let mut current = &self.root;
+        let mut path = path;
+
+        loop {
+            let size = current.rule.origin().len();
+            if !matches!(current.rule.longest(path, false), Some(share) if share.len() == size) {
+                break;
+            }
+
+            path = &path[size..];
+
+            if let Some((key, data)) = current.item_ref() {
+                best = Some((key, data, path));
+            }
+
+            current = match path.first().and_then(|&byte| current.next.regular.get(byte as usize)) {
+                Some(node) => node,
+                None => break,
+            };
+        }
+
+        best
+    }
+
+    /// Check if the tree contains the key
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{PlainRadixMap, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut map = PlainRadixMap::new();
+    ///     map.insert(&b"\x00\xff\x7f"[..], ())?;
+    ///
+    ///     assert_eq!(map.contains_key(b"\x00\xff\x7f"), true);
+    ///     assert_eq!(map.contains_key(b"\x00\xff"), false);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn contains_key(&self, path: &[u8]) -> bool {
+        self.get(path).is_some()
+    }
+
+    /// Iterate over the tree to retrieve nodes' key and value
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use radixmap::{PlainRadixMap, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let map = PlainRadixMap::try_from([("/api/v1", 1), ("/api/v2", 2), ("/api", 0)])?;
+    ///     let mut iter = map.iter();
+    ///
+    ///     assert_eq!(iter.next(), Some((&Bytes::from("/api"), &0)));
+    ///     assert_eq!(iter.next(), Some((&Bytes::from("/api/v1"), &1)));
+    ///     assert_eq!(iter.next(), Some((&Bytes::from("/api/v2"), &2)));
+    ///     assert_eq!(iter.next(), None);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, V> {
+        Iter { iter: node::Iter::from(&self.root) }
+    }
+
+    /// Mutable iterator for the tree
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{PlainRadixMap, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut map = PlainRadixMap::try_from([("/api/v1", 1), ("/api/v2", 2)])?;
+    ///
+    ///     for (_, data) in map.iter_mut() {
+    ///         *data *= 10;
+    ///     }
+    ///
+    ///     assert_eq!(map.get(b"/api/v1"), Some(&10));
+    ///     assert_eq!(map.get(b"/api/v2"), Some(&20));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn iter_mut(&mut self) -> IterMut<'_, V> {
+        IterMut { iter: node::IterMut::from(&mut self.root) }
+    }
+
+    /// Iterate over the tree to get nodes' key only
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use radixmap::{PlainRadixMap, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let map = PlainRadixMap::try_from([("/api/v1", 1), ("/api/v2", 2)])?;
+    ///     let mut iter = map.keys();
+    ///
+    ///     assert_eq!(iter.next(), Some(&Bytes::from("/api/v1")));
+    ///     assert_eq!(iter.next(), Some(&Bytes::from("/api/v2")));
+    ///     assert_eq!(iter.next(), None);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn keys(&self) -> Keys<'_, V> {
+        Keys { iter: self.iter() }
+    }
+
+    /// Iterate over the tree to get nodes' value only
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{PlainRadixMap, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let map = PlainRadixMap::try_from([("/api/v1", 1), ("/api/v2", 2)])?;
+    ///     let mut iter = map.values();
+    ///
+    ///     assert_eq!(iter.next(), Some(&1));
+    ///     assert_eq!(iter.next(), Some(&2));
+    ///     assert_eq!(iter.next(), None);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn values(&self) -> Values<'_, V> {
+        Values { iter: self.iter() }
+    }
+
+    /// Iterate over the tree to get nodes' mutable value
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{PlainRadixMap, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut map = PlainRadixMap::try_from([("/api/v1", 1), ("/api/v2", 2)])?;
+    ///
+    ///     for data in map.values_mut() {
+    ///         *data += 1;
+    ///     }
+    ///
+    ///     assert_eq!(map.values().copied().collect::<Vec<_>>(), vec![2, 3]);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn values_mut(&mut self) -> ValuesMut<'_, V> {
+        ValuesMut { iter: self.iter_mut() }
+    }
+
+    /// Insert into a pair of new data and return old if exist, the key is kept as it is
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{PlainRadixMap, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut map = PlainRadixMap::new();
+    ///
+    ///     assert_eq!(map.insert("/v1/projects/p1:batchGet", "batch")?, None);
+    ///     assert_eq!(map.insert("/v1/projects/p1:batchGet", "again")?, Some("batch"));
+    ///     assert_eq!(map.insert(r"C:\Windows\*", "windows")?, None);
+    ///     assert!(map.insert("", "empty").is_err());
+    ///
+    ///     assert_eq!(map.get(br"C:\Windows\*"), Some(&"windows"));
+    ///     assert_eq!(map.len(), 2);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn insert(&mut self, path: impl Into<Bytes>, data: V) -> RadixResult<Option<V>> {
+        let path = path.into();
+        let node = self.root.next.insert(RadixRule::from_plain(path.clone())?)?;
+
+        node.path = path;
+
+        let prev = node.data.replace(data);
+        if prev.is_none() {
+            self.size += 1;
+        }
+
+        Ok(prev)
+    }
+
+    /// Remove the key and return its data, the empty nodes are pruned along the way
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use radixmap::{PlainRadixMap, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut map = PlainRadixMap::new();
+    ///     map.insert("/api/v1", "v1")?;
+    ///     map.insert("/api/v2", "v2")?;
+    ///
+    ///     assert_eq!(map.remove(b"/api"), None);
+    ///     assert_eq!(map.remove(b"/api/v2"), Some((Bytes::from("/api/v2"), "v2")));
+    ///     assert_eq!(map.remove(b"/api/v2"), None);
+    ///     assert_eq!(map.get(b"/api/v1"), Some(&"v1"));
+    ///     assert_eq!(map.len(), 1);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn remove(&mut self, path: &[u8]) -> Option<(Bytes, V)> {
+        let node = self.lookup_mut(path)?;
+        let data = node.data.take()?;
+        let key = core::mem::take(&mut node.path);
+
+        self.size -= 1;
+        self.root.prune(path);
+
+        Some((key, data))
+    }
+
+    /// Clear the nodes but preserve its capacity
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{PlainRadixMap, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut map = PlainRadixMap::try_from([("/api/v1", ()), ("/api/v2", ())])?;
+    ///
+    ///     map.clear();
+    ///
+    ///     assert_eq!(map.is_empty(), true);
+    ///     assert_eq!(map.len(), 0);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn clear(&mut self) {
+        self.root.clear();
+        self.size = 0;
+    }
+
+    /// Internal use only, descend through the regular children whose fragments prefix the path
+    fn lookup(&self, mut path: &[u8]) -> Option<&RadixNode<V>> {
+        let mut current = &self.root;
+
+        loop {
+            let size = current.rule.origin().len();
+            if current.rule.longest(path, false)?.len() != size {
+                return None;
+            }
+
+            path = &path[size..];
+
+            current = match path.first() {
+                Some(&byte) => current.next.regular.get(byte as usize)?,
+                None => return Some(current),
+            };
+        }
+    }
+
+    /// Internal use only, same as lookup
+    fn lookup_mut(&mut self, mut path: &[u8]) -> Option<&mut RadixNode<V>> {
+        let mut current = &mut self.root;
+
+        loop {
+            let size = current.rule.origin().len();
+            if current.rule.longest(path, false)?.len() != size {
+                return None;
+            }
+
+            path = &path[size..];
+
+            current = match path.first() {
+                Some(&byte) => current.next.regular.get_mut(byte as usize)?,
+                None => return Some(current),
+            };
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+
+/// Construct from an array of tuples
+///
+/// # Examples
+///
+/// ```
+/// use radixmap::{PlainRadixMap, RadixResult};
+///
+/// fn main() -> RadixResult<()> {
+///     let map = PlainRadixMap::try_from([("/api/:id", 1), ("/api/*", 2)])?;
+///
+///     assert_eq!(map.len(), 2);
+///     assert_eq!(map.get(b"/api/:id"), Some(&1));
+///     assert_eq!(map.get(b"/api/*"), Some(&2));
+///     assert_eq!(map.get(b"/api/1"), None);
+///
+///     Ok(())
+/// }
+/// ```
+impl<V, const N: usize> TryFrom<[(Bytes, V); N]> for PlainRadixMap<V> {
+    type Error = RadixError;
+
+    #[inline]
+    fn try_from(value: [(Bytes, V); N]) -> Result<Self, Self::Error> {
+        let mut map = PlainRadixMap::default();
+
+        for (path, data) in value {
+            map.insert(path, data)?;
+        }
+
+        Ok(map)
+    }
+}
+
+/// Construct from an array of tuples
+impl<V, const N: usize> TryFrom<[(&'static [u8], V); N]> for PlainRadixMap<V> {
+    type Error = RadixError;
+
+    #[inline]
+    fn try_from(value: [(&'static [u8], V); N]) -> Result<Self, Self::Error> {
+        value.map(|(k, v)| (Bytes::from(k), v)).try_into()
+    }
+}
+
+/// Construct from an array of tuples
+impl<V, const N: usize> TryFrom<[(&'static str, V); N]> for PlainRadixMap<V> {
+    type Error = RadixError;
+
+    #[inline]
+    fn try_from(value: [(&'static str, V); N]) -> Result<Self, Self::Error> {
+        value.map(|(k, v)| (Bytes::from(k), v)).try_into()
+    }
+}
+
+/// Default trait
+impl<V> Default for PlainRadixMap<V> {
+    #[inline]
+    fn default() -> Self {
+        Self { root: RadixNode::default(), size: 0 }
+    }
+}
+
+/// Clone trait
+impl<V: Clone> Clone for PlainRadixMap<V> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self { root: self.root.clone(), size: self.size }
+    }
+}
+
+/// Debug trait
+///
+/// # Examples
+///
+/// ```
+/// use radixmap::{PlainRadixMap, RadixResult};
+///
+/// fn main() -> RadixResult<()> {
+///     let map = PlainRadixMap::try_from([("/api/v1", 1), ("/api/v2", 2)])?;
+///
+///     assert_eq!(format!("{:?}", map).as_str(), r#"{b"/api/v1": 1, b"/api/v2": 2}"#);
+///
+///     Ok(())
+/// }
+/// ```
+impl<V: Debug> Debug for PlainRadixMap<V> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// == & !=
+impl<V: Eq> Eq for PlainRadixMap<V> {}
+
+/// == & !=
+///
+/// # Examples
+///
+/// ```
+/// use radixmap::{PlainRadixMap, RadixResult};
+///
+/// fn main() -> RadixResult<()> {
+///     let map_a = PlainRadixMap::try_from([("/api/v1", 1), ("/api/v2", 2)])?;
+///     let map_b = map_a.clone();
+///     let map_c = PlainRadixMap::try_from([("/api/v1", 1), ("/api/v3", 2)])?;
+///
+///     assert_eq!(map_a, map_b);
+///     assert_ne!(map_a, map_c);
+///
+///     Ok(())
+/// }
+/// ```
+impl<V: PartialEq> PartialEq for PlainRadixMap<V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+/// Get data from map
+///
+/// # Examples
+///
+/// ```
+/// use std::panic::catch_unwind;
+/// use radixmap::{PlainRadixMap, RadixResult};
+///
+/// fn main() -> RadixResult<()> {
+///     let map = PlainRadixMap::try_from([("/api/v1", 1), ("/api/v2", 2)])?;
+///
+///     assert_eq!(map[b"/api/v1"], 1);
+///     assert_eq!(map[b"/api/v2"], 2);
+///     assert_eq!(catch_unwind(|| map[b"/api/v3"]).is_err(), true);
+///
+///     Ok(())
+/// }
+/// ```
+impl<V> Index<&[u8]> for PlainRadixMap<V> {
+    type Output = V;
+
+    fn index(&self, path: &[u8]) -> &Self::Output {
+        self.get(path).unwrap_or_else(|| panic!("path not found"))
+    }
+}
+
+/// Get/Set data from map
+impl<V> IndexMut<&[u8]> for PlainRadixMap<V> {
+    fn index_mut(&mut self, path: &[u8]) -> &mut Self::Output {
+        self.get_mut(path).unwrap_or_else(|| panic!("path not found"))
+    }
+}
+
+// -----------------------------------------------------------------------------
+
+/// Radix set which treats every key as plain bytes, build on top of map
+pub struct PlainRadixSet {
+    /// The internal map
+    base: PlainRadixMap<()>,
+}
+
+impl PlainRadixSet {
+    /// For consistency with the standard library, we provide this fn to create an empty set
+    #[inline]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// The data nodes' count
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{PlainRadixSet, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut set = PlainRadixSet::new();
+    ///     assert_eq!(set.len(), 0);
+    ///     assert_eq!(set.is_empty(), true);
+    ///
+    ///     set.insert("/api/*")?;
+    ///     assert_eq!(set.len(), 1);
+    ///     assert_eq!(set.is_empty(), false);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.base.len()
+    }
+
+    /// Check if the tree has no data nodes
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.base.is_empty()
+    }
+
+    /// Check if the tree contains the key
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{PlainRadixSet, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let set = PlainRadixSet::try_from(["apple", "app", "{brace}"])?;
+    ///
+    ///     assert_eq!(set.contains(b"app"), true);
+    ///     assert_eq!(set.contains(b"apple"), true);
+    ///     assert_eq!(set.contains(b"{brace}"), true);
+    ///     assert_eq!(set.contains(b"appl"), false);
+    ///     assert_eq!(set.contains(b"brace"), false);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn contains(&self, path: &[u8]) -> bool {
+        self.base.contains_key(path)
+    }
+
+    /// Find the longest key which is a prefix of the path, along with the unmatched remainder
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use radixmap::{PlainRadixSet, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let set = PlainRadixSet::try_from(["app", "apple"])?;
+    ///
+    ///     assert_eq!(set.longest_prefix(b"applesauce"), Some((&Bytes::from("apple"), b"sauce".as_ref())));
+    ///     assert_eq!(set.longest_prefix(b"appl"), Some((&Bytes::from("app"), b"l".as_ref())));
+    ///     assert_eq!(set.longest_prefix(b"ap"), None);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn longest_prefix<'u>(&self, path: &'u [u8]) -> Option<(&Bytes, &'u [u8])> {
+        self.base.longest_prefix(path).map(|(path, _, rest)| (path, rest))
+    }
+
+    /// Iterate over the tree to retrieve nodes' key
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use radixmap::{PlainRadixSet, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let set = PlainRadixSet::try_from(["/api/v1", "/api/v2", "/api"])?;
+    ///     let mut iter = set.iter();
+    ///
+    ///     assert_eq!(iter.next(), Some(&Bytes::from("/api")));
+    ///     assert_eq!(iter.next(), Some(&Bytes::from("/api/v1")));
+    ///     assert_eq!(iter.next(), Some(&Bytes::from("/api/v2")));
+    ///     assert_eq!(iter.next(), None);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn iter(&self) -> Keys<'_, ()> {
+        self.base.keys()
+    }
+
+    /// Insert into new data and return true if exist
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{PlainRadixSet, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut set = PlainRadixSet::new();
+    ///
+    ///     assert_eq!(set.insert("/api/:id")?, false);
+    ///     assert_eq!(set.insert("/api/:id")?, true);
+    ///     assert!(set.insert("").is_err());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn insert(&mut self, path: impl Into<Bytes>) -> RadixResult<bool> {
+        self.base.insert(path, ()).map(|data| data.is_some())
+    }
+
+    /// Remove the key and return true if exist
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{PlainRadixSet, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut set = PlainRadixSet::try_from(["/api/v1", "/api/v2"])?;
+    ///
+    ///     assert_eq!(set.remove(b"/api/v1"), true);
+    ///     assert_eq!(set.remove(b"/api/v1"), false);
+    ///     assert_eq!(set.len(), 1);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn remove(&mut self, path: &[u8]) -> bool {
+        self.base.remove(path).is_some()
+    }
+
+    /// Clear the nodes but preserve its capacity
+    #[inline]
+    pub fn clear(&mut self) {
+        self.base.clear();
+    }
+}
+
+// -----------------------------------------------------------------------------
+
+/// Construct from an array of keys
+impl<const N: usize> TryFrom<[Bytes; N]> for PlainRadixSet {
+    type Error = RadixError;
+
+    #[inline]
+    fn try_from(value: [Bytes; N]) -> Result<Self, Self::Error> {
+        let mut set = PlainRadixSet::default();
+
+        for path in value {
+            set.insert(path)?;
+        }
+
+        Ok(set)
+    }
+}
+
+/// Construct from an array of keys
+impl<const N: usize> TryFrom<[&'static [u8]; N]> for PlainRadixSet {
+    type Error = RadixError;
+
+    #[inline]
+    fn try_from(value: [&'static [u8]; N]) -> Result<Self, Self::Error> {
+        value.map(Bytes::from).try_into()
+    }
+}
+
+/// Construct from an array of keys
+impl<const N: usize> TryFrom<[&'static str; N]> for PlainRadixSet {
+    type Error = RadixError;
+
+    #[inline]
+    fn try_from(value: [&'static str; N]) -> Result<Self, Self::Error> {
+        value.map(Bytes::from).try_into()
+    }
+}
+
+/// Default trait
+impl Default for PlainRadixSet {
+    #[inline]
+    fn default() -> Self {
+        Self { base: Default::default() }
+    }
+}
+
+/// Clone trait
+impl Clone for PlainRadixSet {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self { base: self.base.clone() }
+    }
+}
+
+/// Debug trait
+///
+/// # Examples
+///
+/// ```
+/// use radixmap::{PlainRadixSet, RadixResult};
+///
+/// fn main() -> RadixResult<()> {
+///     let set = PlainRadixSet::try_from(["/api/v1", "/api/v2"])?;
+///
+///     assert_eq!(format!("{:?}", set).as_str(), r#"{b"/api/v1", b"/api/v2"}"#);
+///
+///     Ok(())
+/// }
+/// ```
+impl Debug for PlainRadixSet {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/// == & !=
+impl Eq for PlainRadixSet {}
+
+/// == & !=
+impl PartialEq for PlainRadixSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.base == other.base
+    }
+}
+
+// -----------------------------------------------------------------------------
+
+/// Re-import Order
+pub type Order = map::Order;
@@ -1,7 +1,8 @@
 //! Rule represents a match
 use super::defs::*;
-use std::str::from_utf8;
-use std::str::from_utf8_unchecked;
+use core::str::from_utf8;
+use core::str::from_utf8_unchecked;
+use core::str::FromStr;
 
 /// An enum representing various matching patterns
 #[derive(Clone)]
@@ -15,15 +16,20 @@ pub enum RadixRule {
     ///
     Plain {
         /// fragment
-        frag: Bytes
+        frag: Bytes,
+
+        /// ignore the ascii case
+        icase: bool,
     },
 
-    /// Named param matches a segment of the route
+    /// Named param matches a segment of the route, or a part of it if a literal follows the
+    /// param in the pattern. The terminators in parentheses also end the param
     ///
     /// # Syntax
     ///
     /// - :
     /// - :id
+    /// - :name(.-)
     ///
     Param {
         /// fragment
@@ -31,20 +37,65 @@ pub enum RadixRule {
 
         /// param's name
         name: Bytes,
+
+        /// the separator which ends the param
+        sep: Bytes,
+
+        /// the extra bytes which end the param
+        stop: Bytes,
     },
 
-    /// Unix glob style matcher, note that it must be the last component of a route
+    /// Named param with a built-in constraint, checked without regex
+    ///
+    /// # Syntax
+    ///
+    /// - :id<u32>
+    /// - :id<i64>
+    /// - :token<uuid>
+    /// - :n<1..=500>
+    /// - :slug<alnum>
+    /// - :major<u8>(.)
+    ///
+    Typed {
+        /// fragment
+        frag: Bytes,
+
+        /// param's name
+        name: Bytes,
+
+        /// the constraint
+        kind: RadixType,
+
+        /// the separator which ends the param
+        sep: Bytes,
+
+        /// the extra bytes which end the param
+        stop: Bytes,
+    },
+
+    /// Unix glob style matcher, note that it must be the last component of a route.
+    /// The named form is a catch-all whose capture is reported under its name
     ///
     /// # Syntax
     ///
     /// - *
+    /// - *.html
+    /// - {*rest}
+    /// - {*rest:*.html}
     ///
+    #[cfg(feature = "glob")]
     Glob {
         /// fragment
         frag: Bytes,
 
+        /// glob's name
+        name: Bytes,
+
         /// glob pattern
-        glob: glob::Pattern
+        glob: glob::Pattern,
+
+        /// ignore the ascii case
+        icase: bool,
     },
 
     /// Perl-like regular expressions
@@ -57,6 +108,7 @@ pub enum RadixRule {
     /// - {:\d+}
     /// - {id:\d+}
     ///
+    #[cfg(feature = "regex")]
     Regex {
         /// fragment
         frag: Bytes,
@@ -67,8 +119,323 @@ pub enum RadixRule {
         /// the regex
         expr: Regex,
     },
+
+    /// Segment matcher registered by the user, see RadixMatcher
+    ///
+    /// # Syntax
+    ///
+    /// - {@hex32}
+    /// - {:@date}
+    /// - {id:@ulid}
+    ///
+    Custom {
+        /// fragment
+        frag: Bytes,
+
+        /// matcher's name
+        name: Bytes,
+
+        /// the matcher
+        matcher: Arc<dyn RadixMatcher>,
+    },
+}
+
+/// User-defined segment matcher, referenced in the pattern as {name:@matcher} after it's
+/// registered in the config. Simple validators such as a checksum or a date are cheaper and
+/// more expressive here than a regex
+///
+/// # Examples
+///
+/// ```
+/// use bytes::Bytes;
+/// use radixmap::{rule::RadixMatcher};
+///
+/// struct Hex32;
+///
+/// impl RadixMatcher for Hex32 {
+///     fn matches(&self, path: &[u8]) -> Option<(usize, Option<Bytes>)> {
+///         let data = path.get(..32)?;
+///
+///         match data.iter().all(u8::is_ascii_hexdigit) {
+///             true => Some((32, Some(Bytes::from(data.to_ascii_lowercase())))),
+///             false => None,
+///         }
+///     }
+/// }
+///
+/// assert_eq!(Hex32.matches(b"0123456789ABCDEF0123456789abcdef/rest").map(|m| m.0), Some(32));
+/// assert_eq!(Hex32.matches(b"0123456789"), None);
+/// ```
+pub trait RadixMatcher: Send + Sync + RefUnwindSafe {
+    /// Match the beginning of the path, return the matched length and an optional normalized
+    /// capture which replaces the matched bytes in the params
+    fn matches(&self, path: &[u8]) -> Option<(usize, Option<Bytes>)>;
+}
+
+/// Closures can be used as matchers
+impl<F> RadixMatcher for F where F: Fn(&[u8]) -> Option<(usize, Option<Bytes>)> + Send + Sync + RefUnwindSafe {
+    #[inline]
+    fn matches(&self, path: &[u8]) -> Option<(usize, Option<Bytes>)> {
+        self(path)
+    }
+}
+
+/// The registered matchers by name
+pub type Matchers = BTreeMap<Bytes, Arc<dyn RadixMatcher>>;
+
+/// Built-in constraints of the typed param
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RadixType {
+    /// Unsigned integer which fits in u8
+    U8,
+
+    /// Unsigned integer which fits in u16
+    U16,
+
+    /// Unsigned integer which fits in u32
+    U32,
+
+    /// Unsigned integer which fits in u64
+    U64,
+
+    /// Signed integer which fits in i8
+    I8,
+
+    /// Signed integer which fits in i16
+    I16,
+
+    /// Signed integer which fits in i32
+    I32,
+
+    /// Signed integer which fits in i64
+    I64,
+
+    /// Hyphenated uuid, e.g. 67e55044-10b1-426f-9247-bb680e5fe0c8
+    Uuid,
+
+    /// Non-empty ascii letters and digits
+    Alnum,
+
+    /// Signed integer in the inclusive range
+    Range(i64, i64),
+}
+
+impl RadixType {
+    /// Check if the segment satisfies the constraint
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{rule::RadixType};
+    ///
+    /// assert!(RadixType::U8.check(b"255"));
+    /// assert!(!RadixType::U8.check(b"256"));
+    /// assert!(!RadixType::U8.check(b"+1"));
+    /// assert!(!RadixType::U8.check(b""));
+    /// assert!(RadixType::I64.check(b"-9223372036854775808"));
+    /// assert!(!RadixType::I64.check(b"9223372036854775808"));
+    /// assert!(RadixType::Uuid.check(b"67e55044-10b1-426f-9247-bb680e5fe0c8"));
+    /// assert!(!RadixType::Uuid.check(b"67e55044-10b1-426f-9247-bb680e5fe0c"));
+    /// assert!(RadixType::Alnum.check(b"abc123"));
+    /// assert!(!RadixType::Alnum.check(b"abc-123"));
+    /// assert!(RadixType::Range(1, 500).check(b"500"));
+    /// assert!(!RadixType::Range(1, 500).check(b"0"));
+    /// ```
+    pub fn check(&self, data: &[u8]) -> bool {
+        match self {
+            RadixType::U8 => Self::integer::<u8>(data).is_some(),
+            RadixType::U16 => Self::integer::<u16>(data).is_some(),
+            RadixType::U32 => Self::integer::<u32>(data).is_some(),
+            RadixType::U64 => Self::integer::<u64>(data).is_some(),
+            RadixType::I8 => Self::integer::<i8>(data).is_some(),
+            RadixType::I16 => Self::integer::<i16>(data).is_some(),
+            RadixType::I32 => Self::integer::<i32>(data).is_some(),
+            RadixType::I64 => Self::integer::<i64>(data).is_some(),
+            RadixType::Uuid => data.len() == 36 && data.iter().enumerate().all(|(i, c)| match i {
+                8 | 13 | 18 | 23 => *c == b'-',
+                _ => c.is_ascii_hexdigit(),
+            }),
+            RadixType::Alnum => !data.is_empty() && data.iter().all(u8::is_ascii_alphanumeric),
+            RadixType::Range(min, max) => Self::integer::<i64>(data).is_some_and(|num| (*min..=*max).contains(&num)),
+        }
+    }
+
+    /// Internal use only, parse decimal digits with an optional minus sign
+    #[inline]
+    fn integer<T: FromStr>(data: &[u8]) -> Option<T> {
+        let digits = data.strip_prefix(b"-").unwrap_or(data);
+        if digits.is_empty() || !digits.iter().all(u8::is_ascii_digit) {
+            return None;
+        }
+
+        from_utf8(data).ok()?.parse().ok()
+    }
+}
+
+/// Parse the constraint inside the angle brackets
+///
+/// # Examples
+///
+/// ```
+/// use radixmap::{rule::RadixType, RadixResult};
+///
+/// fn main() -> RadixResult<()> {
+///     assert_eq!("u32".parse::<RadixType>()?, RadixType::U32);
+///     assert_eq!("uuid".parse::<RadixType>()?, RadixType::Uuid);
+///     assert_eq!("1..=500".parse::<RadixType>()?, RadixType::Range(1, 500));
+///     assert_eq!("-5..5".parse::<RadixType>()?, RadixType::Range(-5, 4));
+///     assert!("1..".parse::<RadixType>().is_err());
+///     assert!("0..0".parse::<RadixType>().is_err());
+///     assert!("f32".parse::<RadixType>().is_err());
+///
+///     Ok(())
+/// }
+/// ```
+impl FromStr for RadixType {
+    type Err = RadixError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let kind = match text {
+            "u8" => RadixType::U8,
+            "u16" => RadixType::U16,
+            "u32" => RadixType::U32,
+            "u64" => RadixType::U64,
+            "i8" => RadixType::I8,
+            "i16" => RadixType::I16,
+            "i32" => RadixType::I32,
+            "i64" => RadixType::I64,
+            "uuid" => RadixType::Uuid,
+            "alnum" => RadixType::Alnum,
+            _ => {
+                let range = match text.split_once("..=") {
+                    Some((min, max)) => min.parse().ok().zip(max.parse().ok()),
+                    None => text.split_once("..").and_then(|(min, max)| {
+                        min.parse().ok().zip(max.parse::<i64>().ok().and_then(|max| max.checked_sub(1)))
+                    }),
+                };
+
+                match range {
+                    Some((min, max)) if min <= max => RadixType::Range(min, max),
+                    _ => return Err(RadixError::PathMalformed("unknown param type")),
+                }
+            }
+        };
+
+        Ok(kind)
+    }
+}
+
+// -----------------------------------------------------------------------------
+
+/// Options which affect how the paths are divided into rules
+#[derive(Clone)]
+pub struct RadixConfig {
+    /// The segment separator, params end at it and it's never treated as a special sign,
+    /// e.g. '/' for urls, '.' for dotted keys and '::' for module paths
+    pub separator: Bytes,
+
+    /// Compare the plain text, glob and regex ignoring the ascii case, the inserted paths
+    /// are kept as they are
+    pub ignore_case: bool,
+
+    /// Record the ambiguous or shadowed routes as warnings instead of rejecting them, which
+    /// is useful when migrating the routes of another router
+    pub lenient: bool,
+
+    /// The custom matchers which the {name:@matcher} segments refer to
+    pub matchers: Matchers,
+}
+
+impl RadixConfig {
+    /// Create a config with a custom separator, which can't be empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{rule::RadixConfig};
+    ///
+    /// assert!(RadixConfig::with_separator(".").is_ok());
+    /// assert!(RadixConfig::with_separator("::").is_ok());
+    /// assert!(RadixConfig::with_separator("").is_err());
+    /// ```
+    #[inline]
+    pub fn with_separator(sep: impl AsRef<[u8]>) -> RadixResult<Self> {
+        match sep.as_ref() {
+            [] => Err(RadixError::PathMalformed("separator is empty")),
+            sep => Ok(Self { separator: Bytes::copy_from_slice(sep), ..Default::default() }),
+        }
+    }
+
+    /// Register a custom matcher, the paths inserted afterwards can refer to it by name
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use radixmap::{rule::RadixConfig, rule::RadixRule, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut config = RadixConfig::default();
+    ///     config.register("even", |path: &[u8]| -> Option<(usize, Option<Bytes>)> {
+    ///         let len = path.iter().take_while(|c| c.is_ascii_digit()).count();
+    ///         match len > 0 && (path[len - 1] - b'0') % 2 == 0 {
+    ///             true => Some((len, None)),
+    ///             false => None,
+    ///         }
+    ///     });
+    ///
+    ///     let rule = RadixRule::analyze("{n:@even}/rest".into(), &config)?;
+    ///
+    ///     assert_eq!(rule, "{n:@even}");
+    ///     assert_eq!(rule.longest(b"1234/rest", false), Some("1234".as_bytes()));
+    ///     assert_eq!(rule.longest(b"1235/rest", false), None);
+    ///     assert!(RadixRule::analyze("{n:@odd}".into(), &config).is_err());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn register(&mut self, name: impl Into<Bytes>, matcher: impl RadixMatcher + 'static) {
+        self.matchers.insert(name.into(), Arc::new(matcher));
+    }
 }
 
+/// Default trait
+///
+/// # Examples
+///
+/// ```
+/// use radixmap::{rule::RadixConfig};
+///
+/// assert_eq!(RadixConfig::default().separator, "/");
+/// assert_eq!(RadixConfig::default().ignore_case, false);
+/// ```
+impl Default for RadixConfig {
+    #[inline]
+    fn default() -> Self {
+        Self { separator: Bytes::from_static(b"/"), ignore_case: false, lenient: false, matchers: Matchers::default() }
+    }
+}
+
+/// Debug trait
+impl Debug for RadixConfig {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("RadixConfig")
+            .field("separator", &self.separator)
+            .field("ignore_case", &self.ignore_case)
+            .field("lenient", &self.lenient)
+            .field("matchers", &self.matchers.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+// -----------------------------------------------------------------------------
+
+/// Default values of the params which are absent from an expanded route
+pub type Defaults = Vec<(Bytes, Bytes)>;
+
+// -----------------------------------------------------------------------------
+
 impl RadixRule {
     /// Create a plain text rule
     ///
@@ -82,7 +449,7 @@ impl RadixRule {
     /// ```
     #[inline]
     pub fn from_plain(frag: impl Into<Bytes>) -> RadixResult<Self> {
-        Ok(Self::Plain { frag: frag.into() })
+        Ok(Self::Plain { frag: frag.into(), icase: false })
     }
 
     /// Create a named param rule
@@ -92,21 +459,75 @@ impl RadixRule {
     /// ```
     /// use radixmap::{rule::RadixRule};
     ///
-    /// assert!(RadixRule::from_param(":").is_ok());   // segment placeholder
-    /// assert!(RadixRule::from_param(":id").is_ok()); // param with a name
-    /// assert!(RadixRule::from_param("").is_err());   // missing :
-    /// assert!(RadixRule::from_param("id").is_err()); // missing :
+    /// assert!(RadixRule::from_param(":").is_ok());        // segment placeholder
+    /// assert!(RadixRule::from_param(":id").is_ok());      // param with a name
+    /// assert!(RadixRule::from_param(":name(.-)").is_ok()); // param with terminators
+    /// assert!(RadixRule::from_param("").is_err());        // missing :
+    /// assert!(RadixRule::from_param("id").is_err());      // missing :
+    /// assert!(RadixRule::from_param(":id()").is_err());   // missing terminators
+    /// assert!(RadixRule::from_param(":id(.").is_err());   // missing )
     /// ```
     #[inline]
     pub fn from_param(frag: impl Into<Bytes>) -> RadixResult<Self> {
         let frag = frag.into();
 
         if !frag.starts_with(b":") {
-            return Err(RadixError::PathMalformed("param lack of colon"));
+            return Err(RadixError::PathMalformed("param lack of colon").at(&frag, 0, frag.len()));
         }
 
-        let name = frag.slice(1..);
-        Ok(Self::Param { frag, name })
+        let (head, stop) = Self::terminators(&frag)?;
+        let name = head.slice(1..);
+        Ok(Self::Param { frag, name, sep: Bytes::from_static(b"/"), stop })
+    }
+
+    /// Create a named param rule with a built-in constraint
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{rule::RadixRule};
+    ///
+    /// assert!(RadixRule::from_typed(":id<u32>").is_ok());     // unsigned integer
+    /// assert!(RadixRule::from_typed(":id<i64>").is_ok());     // signed integer
+    /// assert!(RadixRule::from_typed(":n<1..=500>").is_ok());  // integer range
+    /// assert!(RadixRule::from_typed(":<uuid>").is_ok());      // name is empty
+    /// assert!(RadixRule::from_typed(":id<u8>(.)").is_ok());   // with terminators
+    /// assert!(RadixRule::from_typed(":id<f32>").is_err());    // unknown type
+    /// assert!(RadixRule::from_typed(":id<u32").is_err());     // missing >
+    /// assert!(RadixRule::from_typed("id<u32>").is_err());     // missing :
+    /// ```
+    #[inline]
+    pub fn from_typed(frag: impl Into<Bytes>) -> RadixResult<Self> {
+        let frag = frag.into();
+
+        if !frag.starts_with(b":") {
+            return Err(RadixError::PathMalformed("param lack of colon").at(&frag, 0, frag.len()));
+        }
+
+        let (head, stop) = Self::terminators(&frag)?;
+        let pos = match memchr::memchr(b'<', head.as_ref()) {
+            Some(pos) if head.ends_with(b">") => pos,
+            _ => return Err(RadixError::PathMalformed("typed param lack of angle brackets").at(&frag, 0, head.len())),
+        };
+
+        let name = head.slice(1..pos);
+        let kind = Self::utf8(&frag, pos + 1, &head[pos + 1..head.len() - 1])?.parse().map_err(|err: RadixError| err.at(&frag, pos, head.len() - pos))?;
+
+        Ok(Self::Typed { frag, name, kind, sep: Bytes::from_static(b"/"), stop })
+    }
+
+    /// Internal use only, split the param into the head and the terminators in parentheses
+    fn terminators(frag: &Bytes) -> RadixResult<(Bytes, Bytes)> {
+        let pos = match memchr::memchr(b'(', frag.as_ref()) {
+            Some(pos) => pos,
+            None => return Ok((frag.clone(), Bytes::new())),
+        };
+
+        match frag.ends_with(b")") {
+            true if pos + 2 < frag.len() => Ok((frag.slice(..pos), frag.slice(pos + 1..frag.len() - 1))),
+            true => Err(RadixError::PathMalformed("param lack of terminators").at(frag, pos, frag.len() - pos)),
+            false => Err(RadixError::PathMalformed("missing closing sign ')'").at(frag, pos, frag.len() - pos)),
+        }
     }
 
     /// Create a unix glob style rule
@@ -116,21 +537,54 @@ impl RadixRule {
     /// ```
     /// use radixmap::{rule::RadixRule};
     ///
-    /// assert!(RadixRule::from_glob("*").is_ok());      // match entire string
-    /// assert!(RadixRule::from_glob("*id").is_ok());    // match strings ending with 'id'
-    /// assert!(RadixRule::from_glob("").is_err());      // missing rule chars
-    /// assert!(RadixRule::from_glob("id").is_err());    // missing rule chars
+    /// assert!(RadixRule::from_glob("*").is_ok());             // match entire string
+    /// assert!(RadixRule::from_glob("*id").is_ok());           // match strings ending with 'id'
+    /// assert!(RadixRule::from_glob("{*rest}").is_ok());       // named catch-all
+    /// assert!(RadixRule::from_glob("{*rest:*.html}").is_ok()); // named glob
+    /// assert!(RadixRule::from_glob("").is_err());             // missing rule chars
+    /// assert!(RadixRule::from_glob("id").is_err());           // missing rule chars
+    /// assert!(RadixRule::from_glob("{*}").is_err());          // missing name
+    /// assert!(RadixRule::from_glob("{*rest").is_err());       // missing }
     /// ```
+    #[cfg(feature = "glob")]
     #[inline]
     pub fn from_glob(frag: impl Into<Bytes>) -> RadixResult<Self> {
         let frag = frag.into();
 
-        if !frag.starts_with(b"*") {
-            return Err(RadixError::PathMalformed("glob lack of asterisk"));
+        // the named form wraps the name and an optional pattern in curly braces
+        let (name, expr, start) = match frag.starts_with(b"{*") {
+            true if frag.ends_with(b"}") => {
+                let data = frag.slice(2..frag.len() - 1);
+                match memchr::memchr(b':', data.as_ref()) {
+                    Some(pos) => (data.slice(..pos), data.slice(pos + 1..), pos + 3),
+                    None => (data, Bytes::from_static(b"*"), 0),
+                }
+            }
+            true => return Err(RadixError::PathMalformed("missing closing sign '}'").at(&frag, 0, frag.len())),
+            false if frag.starts_with(b"*") => (Bytes::new(), frag.clone(), 0),
+            false => return Err(RadixError::PathMalformed("glob lack of asterisk").at(&frag, 0, frag.len())),
+        };
+
+        if frag.starts_with(b"{") && name.is_empty() {
+            return Err(RadixError::PathMalformed("catch-all lack of name").at(&frag, 0, frag.len()));
         }
 
-        let glob = glob::Pattern::new(from_utf8(frag.as_ref())?)?;
-        Ok(Self::Glob { frag, glob })
+        // the glob reports the position in chars
+        let text = Self::utf8(&frag, start, &expr)?;
+        let glob = glob::Pattern::new(text).map_err(|err| {
+            let pos = text.char_indices().nth(err.pos).map_or(text.len(), |(pos, _)| pos);
+            RadixError::from(err).at(&frag, start + pos, 1)
+        })?;
+
+        Ok(Self::Glob { frag, name, glob, icase: false })
+    }
+
+    /// The glob support is disabled, enable the `glob` feature to use it
+    #[cfg(not(feature = "glob"))]
+    #[inline]
+    pub fn from_glob(frag: impl Into<Bytes>) -> RadixResult<Self> {
+        let frag = frag.into();
+        Err(RadixError::FeatureDisabled("glob").at(&frag, 0, frag.len()))
     }
 
     /// Create a regular expression rule
@@ -156,29 +610,96 @@ impl RadixRule {
     /// let invalid = format!("{{{}}}", unsafe { std::str::from_utf8_unchecked(&[0xffu8, 0xfe, 0x65]) });
     /// assert!(RadixRule::from_regex(invalid).is_err());
     /// ```
+    #[cfg(feature = "regex")]
     #[inline]
     pub fn from_regex(frag: impl Into<Bytes>) -> RadixResult<Self> {
         let frag = frag.into();
 
         if !frag.starts_with(b"{") || !frag.ends_with(b"}") {
-            return Err(RadixError::PathMalformed("regex lack of curly braces"));
+            return Err(RadixError::PathMalformed("regex lack of curly braces").at(&frag, 0, frag.len()));
         }
 
         let data = frag.slice(1..frag.len() - 1);
-        let find = match memchr::memchr(b':', data.as_ref()) {
-            Some(pos) => (data.slice(..pos), from_utf8(&data[pos + 1..])?),
-            None => (Bytes::new(), from_utf8(data.as_ref())?)
+        let (name, start) = match memchr::memchr(b':', data.as_ref()) {
+            Some(pos) => (data.slice(..pos), pos + 2),
+            None => (Bytes::new(), 1)
         };
 
+        let text = Self::utf8(&frag, start, &frag[start..frag.len() - 1])?;
+
         // regex must match from the beginning, add ^ if needed
-        let (name, expr) = match find.1.as_bytes().first() {
-            Some(b'^') => (find.0, Regex::new(find.1)?),
-            _ => (find.0, Regex::new(('^'.to_string() + find.1).as_str())?)
+        let expr = match text.as_bytes().first() {
+            Some(b'^') => Regex::new(text),
+            _ => Regex::new(('^'.to_string() + text).as_str())
         };
 
+        let expr = expr.map_err(|err| RadixError::from(err).at(&frag, start, text.len()))?;
         Ok(Self::Regex { frag, name, expr })
     }
 
+    /// The regex support is disabled, enable the `regex` feature to use it
+    #[cfg(not(feature = "regex"))]
+    #[inline]
+    pub fn from_regex(frag: impl Into<Bytes>) -> RadixResult<Self> {
+        let frag = frag.into();
+        Err(RadixError::FeatureDisabled("regex").at(&frag, 0, frag.len()))
+    }
+
+    /// Create a custom matcher rule, the matcher's name in the fragment is informative only
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use radixmap::{rule::RadixRule};
+    ///
+    /// let digits = |path: &[u8]| -> Option<(usize, Option<Bytes>)> {
+    ///     Some((path.iter().take_while(|c| c.is_ascii_digit()).count(), None))
+    /// };
+    ///
+    /// assert!(RadixRule::from_custom("{@digits}", digits).is_ok());    // name is empty
+    /// assert!(RadixRule::from_custom("{:@digits}", digits).is_ok());   // same as above
+    /// assert!(RadixRule::from_custom("{id:@digits}", digits).is_ok()); // matcher with a name
+    /// assert!(RadixRule::from_custom("{id:@}", digits).is_err());      // missing matcher
+    /// assert!(RadixRule::from_custom("{id:digits}", digits).is_err()); // missing @
+    /// assert!(RadixRule::from_custom("{id:@digits", digits).is_err()); // missing }
+    /// ```
+    #[inline]
+    pub fn from_custom(frag: impl Into<Bytes>, matcher: impl RadixMatcher + 'static) -> RadixResult<Self> {
+        Self::custom(frag.into(), Arc::new(matcher))
+    }
+
+    /// Internal use only, create a custom matcher rule with a shared matcher
+    fn custom(frag: Bytes, matcher: Arc<dyn RadixMatcher>) -> RadixResult<Self> {
+        let (name, _) = Self::matcher(&frag)?;
+        Ok(Self::Custom { frag, name, matcher })
+    }
+
+    /// Internal use only, split the custom fragment into its name and the matcher's name
+    fn matcher(frag: &Bytes) -> RadixResult<(Bytes, Bytes)> {
+        if !frag.starts_with(b"{") || !frag.ends_with(b"}") {
+            return Err(RadixError::PathMalformed("matcher lack of curly braces").at(frag, 0, frag.len()));
+        }
+
+        let data = frag.slice(1..frag.len() - 1);
+        let (name, kind, start) = match memchr::memchr(b':', data.as_ref()) {
+            Some(pos) => (data.slice(..pos), data.slice(pos + 1..), pos + 2),
+            None => (Bytes::new(), data, 1),
+        };
+
+        match kind.strip_prefix(b"@") {
+            Some(rest) if !rest.is_empty() => Ok((name, kind.slice(1..))),
+            Some(_) => Err(RadixError::PathMalformed("matcher lack of name").at(frag, start, kind.len())),
+            None => Err(RadixError::PathMalformed("matcher lack of at sign").at(frag, start, kind.len())),
+        }
+    }
+
+    /// Internal use only, decode the part of the fragment which starts at the offset
+    #[inline]
+    fn utf8<'a>(frag: &Bytes, start: usize, data: &'a [u8]) -> RadixResult<&'a str> {
+        from_utf8(data).map_err(|err| RadixError::from(err).at(frag, start + err.valid_up_to(), err.error_len().unwrap_or(1)))
+    }
+
     /// Check if the rule is plain text
     ///
     /// # Examples
@@ -186,6 +707,7 @@ impl RadixRule {
     /// ```
     /// use radixmap::{rule::RadixRule, RadixResult};
     ///
+    /// # #[cfg(all(feature = "glob", feature = "regex"))]
     /// fn main() -> RadixResult<()> {
     ///     assert_eq!(RadixRule::from_plain("")?.is_plain(), true);
     ///     assert_eq!(RadixRule::from_param(":id")?.is_plain(), false);
@@ -194,6 +716,8 @@ impl RadixRule {
     ///
     ///     Ok(())
     /// }
+    /// # #[cfg(not(all(feature = "glob", feature = "regex")))]
+    /// # fn main() {}
     /// ```
     #[inline]
     pub fn is_plain(&self) -> bool {
@@ -207,6 +731,7 @@ impl RadixRule {
     /// ```
     /// use radixmap::{rule::RadixRule, RadixResult};
     ///
+    /// # #[cfg(all(feature = "glob", feature = "regex"))]
     /// fn main() -> RadixResult<()> {
     ///     assert_eq!(RadixRule::from_plain("")?.is_special(), false);
     ///     assert_eq!(RadixRule::from_param(":id")?.is_special(), true);
@@ -215,12 +740,81 @@ impl RadixRule {
     ///
     ///     Ok(())
     /// }
+    /// # #[cfg(not(all(feature = "glob", feature = "regex")))]
+    /// # fn main() {}
     /// ```
     #[inline]
     pub fn is_special(&self) -> bool {
         !self.is_plain()
     }
 
+    /// Check if the rule is a glob which takes the rest of any path
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{rule::RadixRule, RadixResult};
+    ///
+    /// # #[cfg(feature = "glob")]
+    /// fn main() -> RadixResult<()> {
+    ///     assert_eq!(RadixRule::from_glob("*")?.is_catch_all(), true);
+    ///     assert_eq!(RadixRule::from_glob("{*rest}")?.is_catch_all(), true);
+    ///     assert_eq!(RadixRule::from_glob("*.html")?.is_catch_all(), false);
+    ///     assert_eq!(RadixRule::from_param(":id")?.is_catch_all(), false);
+    ///
+    ///     Ok(())
+    /// }
+    /// # #[cfg(not(feature = "glob"))]
+    /// # fn main() {}
+    /// ```
+    #[inline]
+    pub fn is_catch_all(&self) -> bool {
+        match self {
+            #[cfg(feature = "glob")]
+            RadixRule::Glob { glob, .. } => glob.as_str() == "*",
+            _ => false,
+        }
+    }
+
+    /// Check if two special rules match exactly the same paths, only their names differ.
+    /// Such siblings are ambiguous since the latter one is never reached
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{rule::RadixRule, RadixResult};
+    ///
+    /// # #[cfg(all(feature = "glob", feature = "regex"))]
+    /// fn main() -> RadixResult<()> {
+    ///     assert_eq!(RadixRule::from_param(":id")?.is_equivalent(&RadixRule::from_param(":name")?), true);
+    ///     assert_eq!(RadixRule::from_param(":id")?.is_equivalent(&RadixRule::from_param(":id(.)")?), false);
+    ///     assert_eq!(RadixRule::from_typed(":id<u32>")?.is_equivalent(&RadixRule::from_typed(":n<u32>")?), true);
+    ///     assert_eq!(RadixRule::from_typed(":id<u32>")?.is_equivalent(&RadixRule::from_typed(":id<u64>")?), false);
+    ///     assert_eq!(RadixRule::from_glob("*")?.is_equivalent(&RadixRule::from_glob("{*rest}")?), true);
+    ///     assert_eq!(RadixRule::from_regex(r"{id:\d+}")?.is_equivalent(&RadixRule::from_regex(r"{n:\d+}")?), true);
+    ///     assert_eq!(RadixRule::from_regex(r"{id:\d+}")?.is_equivalent(&RadixRule::from_param(":id")?), false);
+    ///     assert_eq!(RadixRule::from_plain("/api")?.is_equivalent(&RadixRule::from_plain("/api")?), false);
+    ///
+    ///     Ok(())
+    /// }
+    /// # #[cfg(not(all(feature = "glob", feature = "regex")))]
+    /// # fn main() {}
+    /// ```
+    pub fn is_equivalent(&self, other: &RadixRule) -> bool {
+        match (self, other) {
+            (RadixRule::Param { sep: a, stop: x, .. }, RadixRule::Param { sep: b, stop: y, .. }) => a == b && x == y,
+            (RadixRule::Typed { kind: k, sep: a, stop: x, .. }, RadixRule::Typed { kind: l, sep: b, stop: y, .. }) => k == l && a == b && x == y,
+            #[cfg(feature = "glob")]
+            (RadixRule::Glob { glob: a, icase: x, .. }, RadixRule::Glob { glob: b, icase: y, .. }) => a == b && x == y,
+            #[cfg(feature = "regex")]
+            (RadixRule::Regex { expr: a, .. }, RadixRule::Regex { expr: b, .. }) => a.as_str() == b.as_str(),
+            (RadixRule::Custom { frag: a, .. }, RadixRule::Custom { frag: b, .. }) => {
+                matches!((Self::matcher(a), Self::matcher(b)), (Ok((_, x)), Ok((_, y))) if x == y)
+            }
+            _ => false,
+        }
+    }
+
     /// Match the path to find the longest shared segment
     ///
     /// # Examples
@@ -228,6 +822,7 @@ impl RadixRule {
     /// ```
     /// use radixmap::{rule::RadixRule, RadixResult};
     ///
+    /// # #[cfg(all(feature = "glob", feature = "regex"))]
     /// fn main() -> RadixResult<()> {
     ///     assert_eq!(RadixRule::from_plain("")?.longest(b"", false), Some("".as_bytes()));
     ///     assert_eq!(RadixRule::from_plain("")?.longest(b"api", false), Some("".as_bytes()));
@@ -241,10 +836,17 @@ impl RadixRule {
     ///     assert_eq!(RadixRule::from_param(":id")?.longest(b"12345/rest", true), Some("".as_bytes()));
     ///     assert_eq!(RadixRule::from_param(":id")?.longest(b":id", true), Some(":id".as_bytes()));
     ///
+    ///     assert_eq!(RadixRule::from_typed(":id<u8>")?.longest(b"255/rest", false), Some("255".as_bytes()));
+    ///     assert_eq!(RadixRule::from_typed(":id<u8>")?.longest(b"256/rest", false), None);
+    ///     assert_eq!(RadixRule::from_typed(":id<u8>")?.longest(b":id<u8>", true), Some(":id<u8>".as_bytes()));
+    ///
     ///     assert_eq!(RadixRule::from_glob("*")?.longest(b"12345/rest", false), Some("12345/rest".as_bytes()));
     ///     assert_eq!(RadixRule::from_glob("*id")?.longest(b"12345/rest", false), None);
     ///     assert_eq!(RadixRule::from_glob("*id")?.longest(b"12345/rest", true), Some("".as_bytes()));
     ///     assert_eq!(RadixRule::from_glob("*id")?.longest(b"*id", true), Some("*id".as_bytes()));
+    ///     assert_eq!(RadixRule::from_glob("{*rest}")?.longest(b"12345/rest", false), Some("12345/rest".as_bytes()));
+    ///     assert_eq!(RadixRule::from_glob("{*rest:*.css}")?.longest(b"css/app.css", false), Some("css/app.css".as_bytes()));
+    ///     assert_eq!(RadixRule::from_glob("{*rest:*.css}")?.longest(b"js/app.js", false), None);
     ///
     ///     assert_eq!(RadixRule::from_regex(r"{}")?.longest(b"12345/rest", false), Some(r"".as_bytes()));
     ///     assert_eq!(RadixRule::from_regex(r"{:}")?.longest(b"12345/rest", false), Some(r"".as_bytes()));
@@ -257,26 +859,37 @@ impl RadixRule {
     ///
     ///     Ok(())
     /// }
+    /// # #[cfg(not(all(feature = "glob", feature = "regex")))]
+    /// # fn main() {}
     /// ```
     #[inline]
     pub fn longest<'u>(&self, path: &'u [u8], raw: bool) -> Option<&'u [u8]> {
+        if let RadixRule::Plain { frag, icase: true } = self {
+            let len = frag.iter().zip(path).take_while(|(a, b)| a.eq_ignore_ascii_case(b)).count();
+            return Some(&path[..len]);
+        }
+
         if matches!(self, RadixRule::Plain { .. }) || raw {
             let frag = match self {
                 RadixRule::Plain { frag, .. } => frag,
                 RadixRule::Param { frag, .. } => frag,
+                RadixRule::Typed { frag, .. } => frag,
+                #[cfg(feature = "glob")]
                 RadixRule::Glob { frag, .. } => frag,
+                #[cfg(feature = "regex")]
                 RadixRule::Regex { frag, .. } => frag,
+                RadixRule::Custom { frag, .. } => frag,
             };
 
             // accelerating string comparison using numbers
-            let min = std::cmp::min(frag.len(), path.len());
+            let min = core::cmp::min(frag.len(), path.len());
             let mut len = 0;
 
-            const BLK: usize = std::mem::size_of::<usize>();
+            const BLK: usize = core::mem::size_of::<usize>();
 
             while len + BLK <= min {
-                let frag_chunk: &usize = unsafe { &*(frag.as_ptr().add(len) as *const usize) };
-                let path_chunk: &usize = unsafe { &*(path.as_ptr().add(len) as *const usize) };
+                let frag_chunk = unsafe { (frag.as_ptr().add(len) as *const usize).read_unaligned() };
+                let path_chunk = unsafe { (path.as_ptr().add(len) as *const usize).read_unaligned() };
 
                 match frag_chunk == path_chunk {
                     true => len += BLK,
@@ -293,22 +906,33 @@ impl RadixRule {
         }
 
         match self {
-            RadixRule::Param { .. } => match memchr::memchr(b'/', path) {
-                Some(p) => Some(&path[..p]),
-                None if !path.is_empty() => Some(path),
-                None => None
+            RadixRule::Param { .. } => match self.segment(path) {
+                Some(data) if !data.is_empty() => Some(data),
+                _ => None
             }
-            RadixRule::Glob { glob, .. } => {
+            RadixRule::Typed { kind, .. } => {
+                let data = self.segment(path)?;
+
+                match kind.check(data) {
+                    true => Some(data),
+                    false => None
+                }
+            }
+            #[cfg(feature = "glob")]
+            RadixRule::Glob { glob, icase, .. } => {
                 let utf8 = match from_utf8(path) {
                     Ok(p) => p,
                     Err(_) => return None,
                 };
 
-                match glob.matches(utf8) {
+                let opts = glob::MatchOptions { case_sensitive: !icase, ..Default::default() };
+
+                match glob.matches_with(utf8, opts) {
                     true => Some(path),
                     false => None
                 }
             }
+            #[cfg(feature = "regex")]
             RadixRule::Regex { expr, .. } => {
                 let utf8 = match from_utf8(path) {
                     Ok(p) => p,
@@ -320,11 +944,181 @@ impl RadixRule {
                     None => None
                 }
             }
+            RadixRule::Custom { matcher, .. } => match matcher.matches(path) {
+                Some((len, _)) if len <= path.len() => Some(&path[..len]),
+                _ => None
+            }
             RadixRule::Plain { .. } => unreachable!(),
         }
     }
 
-    /// Divide the rule into two parts
+    /// The longest part of the path which the param can take, it ends at the separator or any
+    /// of the terminators. Return None if the rule is not a param
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{rule::RadixRule, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     assert_eq!(RadixRule::from_param(":name")?.segment(b"index.html/rest"), Some("index.html".as_bytes()));
+    ///     assert_eq!(RadixRule::from_param(":name(.)")?.segment(b"index.html/rest"), Some("index".as_bytes()));
+    ///     assert_eq!(RadixRule::from_typed(":n<u8>(-)")?.segment(b"1-2"), Some("1".as_bytes()));
+    ///     assert_eq!(RadixRule::from_plain("/api")?.segment(b"/api"), None);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn segment<'u>(&self, path: &'u [u8]) -> Option<&'u [u8]> {
+        let (sep, stop) = match self {
+            RadixRule::Param { sep, stop, .. } => (sep, stop),
+            RadixRule::Typed { sep, stop, .. } => (sep, stop),
+            _ => return None,
+        };
+
+        let data = match Self::search(path, sep) {
+            Some(p) => &path[..p],
+            None => path,
+        };
+
+        match data.iter().position(|c| stop.contains(c)) {
+            Some(p) => Some(&data[..p]),
+            None => Some(data),
+        }
+    }
+
+    /// Same as longest, but also return the normalized capture of the custom matcher, None
+    /// means the matched bytes are captured as they are. The matcher is called only once
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use radixmap::{rule::RadixRule, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let lower = |path: &[u8]| -> Option<(usize, Option<Bytes>)> {
+    ///         Some((path.len(), Some(Bytes::from(path.to_ascii_lowercase()))))
+    ///     };
+    ///
+    ///     assert_eq!(RadixRule::from_custom("{h:@lower}", lower)?.normalize(b"ABC", false), Some(("ABC".as_bytes(), Some(Bytes::from("abc")))));
+    ///     assert_eq!(RadixRule::from_custom("{h:@lower}", lower)?.normalize(b"{h:@lower}", true), Some(("{h:@lower}".as_bytes(), None)));
+    ///     assert_eq!(RadixRule::from_param(":id")?.normalize(b"ABC", false), Some(("ABC".as_bytes(), None)));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn normalize<'u>(&self, path: &'u [u8], raw: bool) -> Option<(&'u [u8], Option<Bytes>)> {
+        match self {
+            RadixRule::Custom { matcher, .. } if !raw => match matcher.matches(path) {
+                Some((len, norm)) if len <= path.len() => Some((&path[..len], norm)),
+                _ => None
+            }
+            _ => self.longest(path, raw).map(|share| (share, None)),
+        }
+    }
+
+    /// Divide the rule into two parts
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{rule::RadixRule, RadixResult};
+    ///
+    /// # #[cfg(all(feature = "glob", feature = "regex"))]
+    /// fn main() -> RadixResult<()> {
+    ///     let mut rule = RadixRule::from_plain("/api")?;
+    ///
+    ///     assert_eq!(rule.divide(1)?, "api");
+    ///     assert_eq!(rule, "/");
+    ///
+    ///     assert!(RadixRule::from_param(":id")?.divide(1).is_err());
+    ///     assert!(RadixRule::from_glob("*")?.divide(1).is_err());
+    ///     assert!(RadixRule::from_regex(r"{id:\d+}")?.divide(1).is_err());
+    ///
+    ///     Ok(())
+    /// }
+    /// # #[cfg(not(all(feature = "glob", feature = "regex")))]
+    /// # fn main() {}
+    /// ```
+    #[inline]
+    pub fn divide(&mut self, len: usize) -> RadixResult<RadixRule> {
+        match self {
+            RadixRule::Plain { frag, icase } if frag.len() > len => {
+                let rule = RadixRule::Plain { frag: frag.slice(len..), icase: *icase };
+                *frag = frag.slice(..len);
+                Ok(rule)
+            }
+            _ => Err(RadixError::RuleIndivisible)
+        }
+    }
+
+    /// Append another plain rule to the end, the reverse of divide
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{rule::RadixRule, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut rule = RadixRule::from_plain("/")?;
+    ///     rule.merge(&RadixRule::from_plain("api")?)?;
+    ///
+    ///     assert_eq!(rule, "/api");
+    ///
+    ///     assert!(rule.merge(&RadixRule::from_param(":id")?).is_err());
+    ///     assert!(RadixRule::from_param(":id")?.merge(&RadixRule::from_plain("/api")?).is_err());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn merge(&mut self, rule: &RadixRule) -> RadixResult<()> {
+        match (self, rule) {
+            (RadixRule::Plain { frag, .. }, RadixRule::Plain { frag: tail, .. }) => {
+                *frag = Bytes::from([frag.as_ref(), tail.as_ref()].concat());
+                Ok(())
+            }
+            _ => Err(RadixError::RuleUnmergeable)
+        }
+    }
+
+    /// Origin fragment of the rule
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{rule::RadixRule, RadixResult};
+    ///
+    /// # #[cfg(all(feature = "glob", feature = "regex"))]
+    /// fn main() -> RadixResult<()> {
+    ///     assert_eq!(RadixRule::from_plain("/api")?.origin(), "/api");
+    ///     assert_eq!(RadixRule::from_param(":id")?.origin(), ":id");
+    ///     assert_eq!(RadixRule::from_glob("*")?.origin(), "*");
+    ///     assert_eq!(RadixRule::from_regex(r"{id:\d+}")?.origin(), r"{id:\d+}");
+    ///
+    ///     Ok(())
+    /// }
+    /// # #[cfg(not(all(feature = "glob", feature = "regex")))]
+    /// # fn main() {}
+    /// ```
+    #[inline]
+    pub fn origin(&self) -> &Bytes {
+        match self {
+            RadixRule::Plain { frag, .. } => frag,
+            RadixRule::Param { frag, .. } => frag,
+            RadixRule::Typed { frag, .. } => frag,
+            #[cfg(feature = "glob")]
+            RadixRule::Glob { frag, .. } => frag,
+            #[cfg(feature = "regex")]
+            RadixRule::Regex { frag, .. } => frag,
+            RadixRule::Custom { frag, .. } => frag,
+        }
+    }
+
+    /// Check if the rule ignores the ascii case
     ///
     /// # Examples
     ///
@@ -332,54 +1126,57 @@ impl RadixRule {
     /// use radixmap::{rule::RadixRule, RadixResult};
     ///
     /// fn main() -> RadixResult<()> {
-    ///     let mut rule = RadixRule::from_plain("/api")?;
-    ///
-    ///     assert_eq!(rule.divide(1)?, "api");
-    ///     assert_eq!(rule, "/");
-    ///
-    ///     assert!(RadixRule::from_param(":id")?.divide(1).is_err());
-    ///     assert!(RadixRule::from_glob("*")?.divide(1).is_err());
-    ///     assert!(RadixRule::from_regex(r"{id:\d+}")?.divide(1).is_err());
+    ///     assert_eq!(RadixRule::from_plain("/api")?.is_icase(), false);
+    ///     assert_eq!(RadixRule::from_plain("/api")?.ignore_case()?.is_icase(), true);
+    ///     assert_eq!(RadixRule::from_param(":id")?.ignore_case()?.is_icase(), false);
     ///
     ///     Ok(())
     /// }
     /// ```
     #[inline]
-    pub fn divide(&mut self, len: usize) -> RadixResult<RadixRule> {
+    pub fn is_icase(&self) -> bool {
         match self {
-            RadixRule::Plain { frag } if frag.len() > len => {
-                let rule = RadixRule::from_plain(frag.slice(len..));
-                *frag = frag.slice(..len);
-                rule
-            }
-            _ => Err(RadixError::RuleIndivisible)
+            RadixRule::Plain { icase, .. } => *icase,
+            #[cfg(feature = "glob")]
+            RadixRule::Glob { icase, .. } => *icase,
+            #[cfg(feature = "regex")]
+            RadixRule::Regex { expr, .. } => expr.as_str().starts_with("(?i)"),
+            _ => false,
         }
     }
 
-    /// Origin fragment of the rule
+    /// Make the plain text, glob and regex ignore the ascii case, params are unaffected
     ///
     /// # Examples
     ///
     /// ```
     /// use radixmap::{rule::RadixRule, RadixResult};
     ///
+    /// # #[cfg(all(feature = "glob", feature = "regex"))]
     /// fn main() -> RadixResult<()> {
-    ///     assert_eq!(RadixRule::from_plain("/api")?.origin(), "/api");
-    ///     assert_eq!(RadixRule::from_param(":id")?.origin(), ":id");
-    ///     assert_eq!(RadixRule::from_glob("*")?.origin(), "*");
-    ///     assert_eq!(RadixRule::from_regex(r"{id:\d+}")?.origin(), r"{id:\d+}");
+    ///     assert_eq!(RadixRule::from_plain("/API")?.ignore_case()?.longest(b"/api/v1", false), Some("/api".as_bytes()));
+    ///     assert_eq!(RadixRule::from_glob("*.HTML")?.ignore_case()?.longest(b"index.html", false), Some("index.html".as_bytes()));
+    ///     assert_eq!(RadixRule::from_regex("{[a-z]+}")?.ignore_case()?.longest(b"ABC/rest", false), Some("ABC".as_bytes()));
     ///
     ///     Ok(())
     /// }
+    /// # #[cfg(not(all(feature = "glob", feature = "regex")))]
+    /// # fn main() {}
     /// ```
-    #[inline]
-    pub fn origin(&self) -> &Bytes {
-        match self {
-            RadixRule::Plain { frag } => frag,
-            RadixRule::Param { frag, .. } => frag,
-            RadixRule::Glob { frag, .. } => frag,
-            RadixRule::Regex { frag, .. } => frag,
-        }
+    pub fn ignore_case(self) -> RadixResult<Self> {
+        let rule = match self {
+            RadixRule::Plain { frag, .. } => RadixRule::Plain { frag, icase: true },
+            #[cfg(feature = "glob")]
+            RadixRule::Glob { frag, name, glob, .. } => RadixRule::Glob { frag, name, glob, icase: true },
+            #[cfg(feature = "regex")]
+            RadixRule::Regex { frag, name, expr } if !expr.as_str().starts_with("(?i)") => {
+                let expr = Regex::new(&format!("(?i){}", expr.as_str())).map_err(|err| RadixError::from(err).at(&frag, 0, frag.len()))?;
+                RadixRule::Regex { frag, name, expr }
+            }
+            rule => rule,
+        };
+
+        Ok(rule)
     }
 
     /// The name of the named param and regex
@@ -389,28 +1186,426 @@ impl RadixRule {
     /// ```
     /// use radixmap::{rule::RadixRule, RadixResult};
     ///
+    /// # #[cfg(all(feature = "glob", feature = "regex"))]
     /// fn main() -> RadixResult<()> {
     ///     assert_eq!(RadixRule::from_param(":id")?.identity(), "id");
     ///     assert_eq!(RadixRule::from_regex(r"{id:\d+}")?.identity(), r"id");
+    ///     assert_eq!(RadixRule::from_typed(":id<u32>")?.identity(), "id");
     ///
     ///     assert_eq!(RadixRule::from_plain("/api")?.identity(), "");
     ///     assert_eq!(RadixRule::from_param(":")?.identity(), "");
     ///     assert_eq!(RadixRule::from_glob("*")?.identity(), "*");
+    ///     assert_eq!(RadixRule::from_glob("{*rest}")?.identity(), "rest");
     ///     assert_eq!(RadixRule::from_regex(r"{\d+}")?.identity(), r"");
     ///
     ///     Ok(())
     /// }
+    /// # #[cfg(not(all(feature = "glob", feature = "regex")))]
+    /// # fn main() {}
     /// ```
     #[inline]
     pub fn identity(&self) -> &Bytes {
         static EMPTY: Bytes = Bytes::new();
+        #[cfg(feature = "glob")]
         static GLOB: Bytes = Bytes::from_static(b"*");
 
         match self {
             RadixRule::Plain { .. } => &EMPTY,
             RadixRule::Param { name, .. } => name,
-            RadixRule::Glob { .. } => &GLOB,
+            RadixRule::Typed { name, .. } => name,
+            #[cfg(feature = "glob")]
+            RadixRule::Glob { name, .. } if name.is_empty() => &GLOB,
+            #[cfg(feature = "glob")]
+            RadixRule::Glob { name, .. } => name,
+            #[cfg(feature = "regex")]
             RadixRule::Regex { name, .. } => name,
+            RadixRule::Custom { name, .. } => name,
+        }
+    }
+
+    /// Internal use only, the lower the rank the earlier the rule is tried
+    #[inline]
+    fn rank(&self) -> u8 {
+        match self {
+            RadixRule::Plain { .. } => 0,
+            #[cfg(feature = "regex")]
+            RadixRule::Regex { .. } => 1,
+            RadixRule::Custom { .. } => 2,
+            RadixRule::Typed { .. } => 3,
+            RadixRule::Param { .. } => 4,
+            #[cfg(feature = "glob")]
+            RadixRule::Glob { .. } => 5,
+        }
+    }
+
+    /// Escape the special signs so that the data is inserted as plain text, which is useful
+    /// for untrusted literal keys
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{rule::RadixRule, RadixMap, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     assert_eq!(RadixRule::escape(b"/api/v1"), "/api/v1");
+    ///     assert_eq!(RadixRule::escape(b"p1:batchGet"), r"p1\:batchGet");
+    ///     assert_eq!(RadixRule::escape(br"{draft}[1]*.txt\"), r"\{draft}\[1\]\*.txt\\");
+    ///
+    ///     let mut map = RadixMap::new();
+    ///     map.insert([b"/files/".as_ref(), &RadixRule::escape(b"{draft}.txt")].concat(), "draft")?;
+    ///
+    ///     assert_eq!(map.get(b"/files/{draft}.txt"), Some(&"draft"));
+    ///     assert_eq!(map.get(b"/files/final.txt"), None);
+    ///
+    ///     // a doubled backslash is an escape too, escape the keys which used to hold it verbatim
+    ///     map.insert(r"/a\\b", "escaped")?;
+    ///     map.insert([b"/c".as_ref(), &RadixRule::escape(br"\\"), b"d"].concat(), "verbatim")?;
+    ///
+    ///     assert_eq!(map.get(br"/a\b"), Some(&"escaped"));
+    ///     assert_eq!(map.get(br"/c\\d"), Some(&"verbatim"));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn escape(data: &[u8]) -> Bytes {
+        let mut text = Vec::with_capacity(data.len());
+
+        for &c in data {
+            if Self::escapable(Some(&c)) {
+                text.push(b'\\');
+            }
+
+            text.push(c);
+        }
+
+        Bytes::from(text)
+    }
+
+    /// Remove the escapes from the pattern, which is the canonical form stored in the plain
+    /// rules. The regex and the glob are kept as they are, and a backslash before other signs
+    /// is an ordinary byte
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{rule::RadixRule};
+    ///
+    /// assert_eq!(RadixRule::unescape(br"/api/v1").as_ref(), br"/api/v1");
+    /// assert_eq!(RadixRule::unescape(br"/p1\:batchGet").as_ref(), br"/p1:batchGet");
+    /// assert_eq!(RadixRule::unescape(br"\{draft}\[1\]\*\\").as_ref(), br"{draft}[1]*\");
+    /// assert_eq!(RadixRule::unescape(br"C:\dir").as_ref(), br"C:\dir");
+    /// assert_eq!(RadixRule::unescape(br"\:{id:\d+\:}").as_ref(), br":{id:\d+\:}");
+    /// assert_eq!(RadixRule::unescape(br"\**\:").as_ref(), br"**\:");
+    /// ```
+    pub fn unescape(path: &[u8]) -> Cow<'_, [u8]> {
+        if memchr::memchr(b'\\', path).is_none() {
+            return Cow::Borrowed(path);
+        }
+
+        let mut text = Vec::with_capacity(path.len());
+        let mut pos = 0;
+
+        while pos < path.len() {
+            match path[pos] {
+                b'\\' if Self::escapable(path.get(pos + 1)) => {
+                    text.push(path[pos + 1]);
+                    pos += 2;
+                    continue;
+                }
+                // regex is copied verbatim
+                b'{' => {
+                    let end = memchr::memchr(b'}', &path[pos..]).map_or(path.len(), |p| pos + p + 1);
+                    text.extend_from_slice(&path[pos..end]);
+                    pos = end;
+                    continue;
+                }
+                // glob takes the rest of the path
+                b'*' => {
+                    text.extend_from_slice(&path[pos..]);
+                    break;
+                }
+                c => text.push(c),
+            }
+
+            pos += 1;
+        }
+
+        Cow::Owned(text)
+    }
+
+    /// Expand the optional segments and the default values into concrete routes.
+    /// The first route contains all segments, the others carry the default values
+    /// of the params they lack.
+    ///
+    /// # Syntax
+    ///
+    /// - /users/:id?             -> /users/:id, /users
+    /// - /page/:n=1              -> /page/:n, /page with n=1
+    /// - /archive[/:year[/:mon]] -> /archive/:year/:mon, /archive/:year, /archive
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use radixmap::{rule::RadixConfig, rule::RadixRule, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let config = RadixConfig::default();
+    ///
+    ///     assert_eq!(RadixRule::expand(&Bytes::from("/api/:id"), &config)?, vec![(Bytes::from("/api/:id"), vec![])]);
+    ///
+    ///     assert_eq!(RadixRule::expand(&Bytes::from("/users/:id?"), &config)?, vec![
+    ///         (Bytes::from("/users/:id"), vec![]),
+    ///         (Bytes::from("/users"), vec![]),
+    ///     ]);
+    ///
+    ///     assert_eq!(RadixRule::expand(&Bytes::from("/page/:n<1..=100>=1"), &config)?, vec![
+    ///         (Bytes::from("/page/:n<1..=100>"), vec![]),
+    ///         (Bytes::from("/page"), vec![(Bytes::from("n"), Bytes::from("1"))]),
+    ///     ]);
+    ///
+    ///     assert_eq!(RadixRule::expand(&Bytes::from("/archive[/:year=2024[/:month]]/posts"), &config)?, vec![
+    ///         (Bytes::from("/archive/:year/:month/posts"), vec![]),
+    ///         (Bytes::from("/archive/:year/posts"), vec![]),
+    ///         (Bytes::from("/archive/posts"), vec![(Bytes::from("year"), Bytes::from("2024"))]),
+    ///     ]);
+    ///
+    ///     assert!(RadixRule::expand(&Bytes::from("/archive[/:year"), &config).is_err());
+    ///     assert!(RadixRule::expand(&Bytes::from("/archive]"), &config).is_err());
+    ///
+    ///     // a group of plain text is rejected rather than taken as optional, escape its brackets
+    ///     assert!(RadixRule::expand(&Bytes::from("/x[1]"), &config).is_err());
+    ///     assert_eq!(RadixRule::expand(&Bytes::from(r"/x\[1\]"), &config)?, vec![(Bytes::from(r"/x\[1\]"), vec![])]);
+    ///
+    ///     // the separator before the param is optional as well
+    ///     assert_eq!(RadixRule::expand(&Bytes::from("db.:name?"), &RadixConfig::with_separator(".")?)?, vec![
+    ///         (Bytes::from("db.:name"), vec![]),
+    ///         (Bytes::from("db"), vec![]),
+    ///     ]);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn expand(path: &Bytes, config: &RadixConfig) -> RadixResult<Vec<(Bytes, Defaults)>> {
+        let (tree, optional) = Self::scan(path, &config.separator)?;
+        if !optional {
+            return Ok(vec![(path.clone(), vec![])]);
+        }
+
+        // different combinations may produce the same route, keep the first one
+        let mut list: Vec<(Bytes, Defaults)> = vec![];
+
+        for (route, defaults) in Self::unfold(&tree) {
+            if list.iter().all(|item| item.0 != route) {
+                list.push((Bytes::from(route), defaults));
+            }
+        }
+
+        Ok(list)
+    }
+
+    /// Internal use only, parse the path into text and optional groups. A group must hold a
+    /// special rule, so a literal bracket in a key is not silently taken as a group
+    fn scan(path: &Bytes, sep: &[u8]) -> RadixResult<(Vec<Piece>, bool)> {
+        let mut stack = vec![vec![]];
+        let mut opens: Vec<(usize, bool)> = vec![];
+        let mut optional = false;
+        let mut pos = 0;
+
+        while pos < path.len() {
+            let depth = stack.len();
+            let top = match stack.last_mut() {
+                Some(top) => top,
+                None => unreachable!()
+            };
+
+            // the separator is plain text even if it contains special signs
+            if path[pos..].starts_with(sep) {
+                Piece::text(top, sep);
+                pos += sep.len();
+                continue;
+            }
+
+            match path[pos] {
+                // the escaped sign is copied along with the backslash
+                b'\\' if Self::escapable(path.get(pos + 1)) => {
+                    Piece::text(top, &path[pos..pos + 2]);
+                    pos += 2;
+                }
+                // regex and glob are copied verbatim
+                b'{' => {
+                    let end = memchr::memchr(b'}', &path[pos..]).map_or(path.len(), |p| pos + p + 1);
+                    Piece::text(top, &path[pos..end]);
+                    Piece::special(&mut opens);
+                    pos = end;
+                }
+                b'*' if depth == 1 => {
+                    Piece::text(top, &path[pos..]);
+                    pos = path.len();
+                }
+                b'*' => {
+                    Piece::text(top, &path[pos..pos + 1]);
+                    Piece::special(&mut opens);
+                    pos += 1;
+                }
+                b'[' => {
+                    stack.push(vec![]);
+                    opens.push((pos, false));
+                    optional = true;
+                    pos += 1;
+                }
+                b']' => {
+                    let group = match stack.pop() {
+                        Some(group) if !stack.is_empty() => group,
+                        _ => return Err(RadixError::PathMalformed("missing opening sign '['").at(path, pos, 1)),
+                    };
+
+                    // the group holds a special rule, so does the outer one
+                    match opens.pop() {
+                        Some((open, false)) => return Err(RadixError::PathMalformed("optional group must contain a param, glob or regex, escape the brackets of a literal one").at(path, open, pos + 1 - open)),
+                        _ => Piece::special(&mut opens),
+                    }
+
+                    if let Some(top) = stack.last_mut() {
+                        top.push(Piece::Group(group));
+                    }
+
+                    pos += 1;
+                }
+                b':' => {
+                    let frag = &path[pos..pos + Self::param(&path[pos..], sep)];
+                    let mut end = pos + frag.len();
+                    Piece::special(&mut opens);
+
+                    // the question mark or the default value follows the param
+                    let (value, mark) = match path.get(end) {
+                        Some(b'?') => {
+                            end += 1;
+                            (None, true)
+                        }
+                        Some(b'=') => {
+                            let tail = &path[end + 1..];
+                            let mut size = tail.iter().position(|c| matches!(c, b'[' | b']')).unwrap_or(tail.len());
+                            if let Some(p) = Self::search(&tail[..size], sep) {
+                                size = p;
+                            }
+
+                            end += 1 + size;
+                            (Some(&tail[..size]), false)
+                        }
+                        _ => (None, false),
+                    };
+
+                    if value.is_none() && !mark {
+                        Piece::text(top, frag);
+                        pos = end;
+                        continue;
+                    }
+
+                    // the bracket group is optional already if the param leads it
+                    if depth > 1 && top.iter().all(|piece| matches!(piece, Piece::Text(text) if text == sep)) {
+                        top.push(Piece::Text(frag.to_vec()));
+                        Piece::defaults_of(top, path, frag, value);
+                        pos = end;
+                        continue;
+                    }
+
+                    // the separator before the param is optional as well
+                    let mut group = vec![];
+                    if let Some(Piece::Text(text)) = top.last_mut() {
+                        if text.ends_with(sep) {
+                            text.truncate(text.len() - sep.len());
+                            group.push(Piece::Text(sep.to_vec()));
+                        }
+                    }
+
+                    group.push(Piece::Text(frag.to_vec()));
+
+                    Piece::defaults_of(&mut group, path, frag, value);
+                    top.push(Piece::Group(group));
+                    optional = true;
+                    pos = end;
+                }
+                _ => {
+                    Piece::text(top, &path[pos..pos + 1]);
+                    pos += 1;
+                }
+            }
+        }
+
+        match stack.pop() {
+            Some(tree) if stack.is_empty() => Ok((tree, optional)),
+            _ => Err(RadixError::PathMalformed("missing closing sign ']'").at(path, opens.last().map_or(0, |open| open.0), 1)),
+        }
+    }
+
+    /// Internal use only, enumerate the combinations of the optional groups
+    fn unfold(tree: &[Piece]) -> Vec<(Vec<u8>, Defaults)> {
+        let mut list = vec![(vec![], vec![])];
+
+        for piece in tree {
+            match piece {
+                Piece::Text(text) => list.iter_mut().for_each(|item| item.0.extend_from_slice(text)),
+                Piece::Default(..) => {}
+                Piece::Group(group) => {
+                    let mut alts = Self::unfold(group);
+                    let mut absent = vec![];
+                    Piece::defaults(group, &mut absent);
+                    alts.push((vec![], absent));
+
+                    list = list.iter().flat_map(|(route, defaults)| alts.iter().map(move |(tail, extra)| {
+                        ([route.as_slice(), tail].concat(), [defaults.as_slice(), extra].concat())
+                    })).collect();
+                }
+            }
+        }
+
+        list
+    }
+}
+
+/// Internal use only, the syntax tree of the optional segments
+enum Piece {
+    Text(Vec<u8>),
+    Default(Bytes, Bytes),
+    Group(Vec<Piece>),
+}
+
+impl Piece {
+    /// Mark the innermost open group as holding a special rule
+    #[inline]
+    fn special(opens: &mut [(usize, bool)]) {
+        if let Some(open) = opens.last_mut() {
+            open.1 = true;
+        }
+    }
+
+    /// Append the text to the last piece if possible
+    #[inline]
+    fn text(list: &mut Vec<Piece>, data: &[u8]) {
+        match list.last_mut() {
+            Some(Piece::Text(text)) => text.extend_from_slice(data),
+            _ => list.push(Piece::Text(data.to_vec())),
+        }
+    }
+
+    /// Append the default value of the param if any
+    #[inline]
+    fn defaults_of(list: &mut Vec<Piece>, path: &Bytes, frag: &[u8], value: Option<&[u8]>) {
+        if let Some(value) = value {
+            let name = &frag[1..frag.iter().position(|c| matches!(c, b'<' | b'(')).unwrap_or(frag.len())];
+            list.push(Piece::Default(path.slice_ref(name), path.slice_ref(value)));
+        }
+    }
+
+    /// Collect the default values inside the group
+    fn defaults(list: &[Piece], out: &mut Defaults) {
+        for piece in list {
+            match piece {
+                Piece::Default(name, value) => out.push((name.clone(), value.clone())),
+                Piece::Group(group) => Piece::defaults(group, out),
+                Piece::Text(_) => {}
+            }
         }
     }
 }
@@ -422,6 +1617,7 @@ impl RadixRule {
 /// ```
 /// use radixmap::{rule::RadixRule, RadixResult};
 ///
+/// # #[cfg(all(feature = "glob", feature = "regex"))]
 /// fn main() -> RadixResult<()> {
 ///     assert!(RadixRule::try_from("").is_err());
 ///
@@ -432,42 +1628,222 @@ impl RadixRule {
 ///     assert_eq!(RadixRule::try_from(":")?, ":");
 ///     assert_eq!(RadixRule::try_from(":id")?, ":id");
 ///     assert_eq!(RadixRule::try_from(":id/rest")?, ":id");
+///     assert_eq!(RadixRule::try_from(":id<u32>/rest")?, RadixRule::from_typed(":id<u32>")?);
+///     assert!(RadixRule::try_from(":id<u32/rest").is_err());
+///     assert_eq!(RadixRule::try_from(":name.:ext")?, ":name");
+///     assert_eq!(RadixRule::try_from(":name(.).:ext")?, ":name(.)");
+///     assert_eq!(RadixRule::try_from(".:ext")?, ".");
 ///
 ///     assert_eq!(RadixRule::try_from("*")?, "*");
 ///     assert_eq!(RadixRule::try_from("*rest")?, "*rest");
 ///     assert_eq!(RadixRule::try_from("*/rest")?, "*/rest");
+///     assert_eq!(RadixRule::try_from("{*rest}")?, "{*rest}");
+///     assert_eq!(RadixRule::try_from("{*rest:*.html}")?, "{*rest:*.html}");
+///     assert!(RadixRule::try_from("{*rest}/index.html").is_err());
 ///
 ///     assert_eq!(RadixRule::try_from(r"{id:\d+}")?, r"{id:\d+}");
 ///     assert_eq!(RadixRule::try_from(r"{id:\d+}/rest")?, r"{id:\d+}");
 ///     assert!(RadixRule::try_from(r"{id:\d+").is_err());
 ///     assert!(RadixRule::try_from(r"{id:\d+/rest").is_err());
 ///
+///     assert_eq!(RadixRule::try_from(r"p1\:batchGet")?, "p1:batchGet");
+///     assert_eq!(RadixRule::try_from(r"\{draft}.txt")?, "{draft}.txt");
+///     assert_eq!(RadixRule::try_from(r"\*.txt")?, "*.txt");
+///     assert_eq!(RadixRule::try_from(r"\d\\:id")?, r"\d\");
+///
 ///     Ok(())
 /// }
+/// # #[cfg(not(all(feature = "glob", feature = "regex")))]
+/// # fn main() {}
 /// ```
 impl TryFrom<Bytes> for RadixRule {
     type Error = RadixError;
 
+    #[inline]
     fn try_from(path: Bytes) -> Result<Self, Self::Error> {
+        Self::analyze(path, &RadixConfig::default())
+    }
+}
+
+impl RadixRule {
+    /// Analyze a path as long as possible and construct a rule, params end at the separator
+    /// of the config, and the separator is always a part of the plain text
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{rule::RadixConfig, rule::RadixRule, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let dot = RadixConfig::with_separator(".")?;
+    ///     let mod_ = RadixConfig::with_separator("::")?;
+    ///
+    ///     assert_eq!(RadixRule::analyze("db.".into(), &dot)?, "db.");
+    ///     assert_eq!(RadixRule::analyze(":name.host".into(), &dot)?, ":name");
+    ///     assert_eq!(RadixRule::analyze(":name-host".into(), &dot)?, ":name");
+    ///     assert_eq!(RadixRule::analyze(":name(-)-host".into(), &dot)?, ":name(-)");
+    ///
+    ///     assert_eq!(RadixRule::analyze("crate::*".into(), &mod_)?, "crate::");
+    ///     assert_eq!(RadixRule::analyze("::*".into(), &mod_)?, "::");
+    ///     assert_eq!(RadixRule::analyze(":name::*".into(), &mod_)?, ":name");
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn analyze(path: Bytes, config: &RadixConfig) -> RadixResult<Self> {
+        Self::consume(path, config).map(|(rule, _)| rule)
+    }
+
+    /// Internal use only, same as analyze, but also report the length of the path taken by
+    /// the rule, which is longer than the fragment if the escapes are removed
+    pub(crate) fn consume(path: Bytes, config: &RadixConfig) -> RadixResult<(Self, usize)> {
+        let sep = &config.separator;
         let init = path.first().ok_or(RadixError::PathEmpty)?;
 
-        match *init {
-            b':' => match memchr::memchr(b'/', path.as_ref()) {
-                Some(pos) => Self::from_param(path.slice(..pos)),
-                _ => Self::from_param(path),
+        // the separator may contain special signs, e.g. '::'
+        let rule = match path.starts_with(sep) || !matches!(*init, b':' | b'*' | b'{') {
+            true => {
+                let size = Self::plain(&path, sep);
+                let rule = match Self::unescape(&path[..size]) {
+                    Cow::Borrowed(_) => Self::from_plain(path.slice(..size))?,
+                    Cow::Owned(frag) => Self::from_plain(frag)?,
+                };
+
+                (rule, size)
+            }
+            false => {
+                let rule = Self::extract(path.clone(), config).map_err(|err| err.at(&path, 0, path.len()))?;
+                let size = rule.origin().len();
+                (rule, size)
+            }
+        };
+
+        match config.ignore_case {
+            true => Ok((rule.0.ignore_case()?, rule.1)),
+            false => Ok(rule),
+        }
+    }
+
+    /// Internal use only, rebuild the rule of a stored node from its fragment. The plain text is
+    /// taken as it is, and the special rule must span the whole fragment
+    #[cfg_attr(not(feature = "std"), allow(dead_code))]
+    pub(crate) fn restore(frag: Bytes, plain: bool, icase: bool, config: &RadixConfig) -> RadixResult<Self> {
+        if plain {
+            return Ok(Self::Plain { frag, icase });
+        }
+
+        if !matches!(frag.first(), Some(b':' | b'*' | b'{')) {
+            return Err(RadixError::PathMalformed("special rule must start with ':', '*' or '{'"));
+        }
+
+        let rule = Self::extract(frag.clone(), config).map_err(|err| err.at(&frag, 0, frag.len()))?;
+        if rule.origin() != &frag {
+            return Err(RadixError::PathMalformed("special rule doesn't span the fragment").at(&frag, 0, frag.len()));
+        }
+
+        match config.ignore_case {
+            true => rule.ignore_case(),
+            false => Ok(rule),
+        }
+    }
+
+    /// Internal use only, construct the special rule at the beginning of the path
+    fn extract(path: Bytes, config: &RadixConfig) -> RadixResult<Self> {
+        let sep = &config.separator;
+
+        match path[0] {
+            b':' => {
+                let frag = path.slice(..Self::param(&path, sep));
+
+                let mut rule = match memchr::memchr(b'<', frag.as_ref()) {
+                    Some(_) => Self::from_typed(frag)?,
+                    None => Self::from_param(frag)?,
+                };
+
+                if let RadixRule::Param { sep: slot, .. } | RadixRule::Typed { sep: slot, .. } = &mut rule {
+                    *slot = sep.clone();
+                }
+
+                Ok(rule)
             }
             b'*' => {
                 Self::from_glob(path)
             }
             b'{' => match memchr::memchr(b'}', path.as_ref()) {
+                Some(pos) if path.starts_with(b"{*") => match pos + 1 == path.len() {
+                    true => Self::from_glob(path),
+                    false => Err(RadixError::PathMalformed("catch-all must be the last segment").at(&path, pos + 1, path.len() - pos - 1)),
+                }
+                Some(pos) if Self::matcher(&path.slice(..pos + 1)).is_ok() => {
+                    let frag = path.slice(..pos + 1);
+                    let (_, kind) = Self::matcher(&frag)?;
+
+                    match config.matchers.get(&kind) {
+                        Some(matcher) => Self::custom(frag, matcher.clone()),
+                        None => Err(RadixError::PathMalformed("matcher not registered").at(&frag, 0, frag.len())),
+                    }
+                }
                 Some(pos) => Self::from_regex(path.slice(..pos + 1)),
-                _ => Err(RadixError::PathMalformed("missing closing sign '}'"))
+                _ => Err(RadixError::PathMalformed("missing closing sign '}'").at(&path, 0, path.len()))
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// Internal use only, the length of the leading param, including its type and terminators.
+    /// The name consists of letters, digits, underscores and non-ascii bytes
+    #[inline]
+    fn param(path: &[u8], sep: &[u8]) -> usize {
+        let mut pos = 1;
+
+        while pos < path.len() && !path[pos..].starts_with(sep) && matches!(path[pos], b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'_' | 0x80..) {
+            pos += 1;
+        }
+
+        for (open, close) in [(b'<', b'>'), (b'(', b')')] {
+            if path.get(pos) == Some(&open) {
+                pos = memchr::memchr(close, &path[pos..]).map_or(path.len(), |p| pos + p + 1);
             }
-            _ => match memchr::memchr3(b'{', b':', b'*', path.as_ref()) {
-                Some(pos) => Self::from_plain(path.slice(..pos)),
-                None => Self::from_plain(path),
+        }
+
+        pos
+    }
+
+    /// Internal use only, the length of the leading plain text, separators and escaped signs
+    /// are skipped
+    #[inline]
+    fn plain(path: &[u8], sep: &[u8]) -> usize {
+        let mut pos = 0;
+
+        while let Some(p) = memchr::memchr3(b'{', b':', b'*', &path[pos..]) {
+            let end = pos + p;
+
+            // the sign is escaped if an odd number of backslashes precede it
+            let back = path[..end].iter().rev().take_while(|&&c| c == b'\\').count();
+
+            match path[end..].starts_with(sep) {
+                true => pos = end + sep.len(),
+                false if back % 2 == 1 => pos = end + 1,
+                false => return end,
             }
         }
+
+        path.len()
+    }
+
+    /// Internal use only, check if the backslash escapes the byte
+    #[inline]
+    fn escapable(byte: Option<&u8>) -> bool {
+        matches!(byte, Some(b'\\' | b':' | b'*' | b'{' | b'[' | b']'))
+    }
+
+    /// Internal use only, find the first separator in the path
+    #[inline]
+    fn search(path: &[u8], sep: &[u8]) -> Option<usize> {
+        match sep {
+            [byte] => memchr::memchr(*byte, path),
+            _ => memchr::memmem::find(path, sep),
+        }
     }
 }
 
@@ -501,7 +1877,7 @@ impl TryFrom<&'static str> for RadixRule {
 impl Default for RadixRule {
     #[inline]
     fn default() -> Self {
-        Self::Plain { frag: Bytes::new() }
+        Self::Plain { frag: Bytes::new(), icase: false }
     }
 }
 
@@ -510,24 +1886,34 @@ impl Default for RadixRule {
 /// # Examples
 ///
 /// ```
+/// use bytes::Bytes;
 /// use radixmap::{rule::RadixRule, RadixResult};
 ///
+/// # #[cfg(all(feature = "glob", feature = "regex"))]
 /// fn main() -> RadixResult<()> {
 ///     assert_eq!(format!("{:?}", RadixRule::from_plain("/api")?).as_str(), "Plain(/api)");
 ///     assert_eq!(format!("{:?}", RadixRule::from_param(":id")?).as_str(), "Param(:id)");
+///     assert_eq!(format!("{:?}", RadixRule::from_typed(":id<u32>")?).as_str(), "Typed(:id<u32>)");
 ///     assert_eq!(format!("{:?}", RadixRule::from_glob("*")?).as_str(), "Glob(*)");
 ///     assert_eq!(format!("{:?}", RadixRule::from_regex(r"{id:\d+}")?).as_str(), r"Regex({id:\d+})");
+///     assert_eq!(format!("{:?}", RadixRule::from_custom("{id:@any}", |p: &[u8]| -> Option<(usize, Option<Bytes>)> { Some((p.len(), None)) })?).as_str(), "Custom({id:@any})");
 ///
 ///     Ok(())
 /// }
+/// # #[cfg(not(all(feature = "glob", feature = "regex")))]
+/// # fn main() {}
 /// ```
 impl Debug for RadixRule {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         let (kind, frag) = match self {
-            RadixRule::Plain { frag } => ("Plain", frag),
+            RadixRule::Plain { frag, .. } => ("Plain", frag),
             RadixRule::Param { frag, .. } => ("Param", frag),
+            RadixRule::Typed { frag, .. } => ("Typed", frag),
+            #[cfg(feature = "glob")]
             RadixRule::Glob { frag, .. } => ("Glob", frag),
+            #[cfg(feature = "regex")]
             RadixRule::Regex { frag, .. } => ("Regex", frag),
+            RadixRule::Custom { frag, .. } => ("Custom", frag),
         };
 
         write!(f, "{}({})", kind, unsafe { from_utf8_unchecked(frag.as_ref()) })
@@ -542,6 +1928,7 @@ impl Debug for RadixRule {
 /// use std::collections::HashMap;
 /// use radixmap::{rule::RadixRule, RadixResult};
 ///
+/// # #[cfg(all(feature = "glob", feature = "regex"))]
 /// fn main() -> RadixResult<()> {
 ///     let mut map = HashMap::new();
 ///     map.insert(RadixRule::from_plain("/api")?, "/api");
@@ -556,12 +1943,14 @@ impl Debug for RadixRule {
 ///
 ///     Ok(())
 /// }
+/// # #[cfg(not(all(feature = "glob", feature = "regex")))]
+/// # fn main() {}
 /// ```
 impl Hash for RadixRule {
     #[inline]
     fn hash<H: Hasher>(&self, state: &mut H) {
         match self {
-            RadixRule::Plain { frag } => {
+            RadixRule::Plain { frag, .. } => {
                 "Plain".hash(state);
                 frag.hash(state);
             }
@@ -569,14 +1958,24 @@ impl Hash for RadixRule {
                 "Param".hash(state);
                 frag.hash(state);
             }
+            RadixRule::Typed { frag, .. } => {
+                "Typed".hash(state);
+                frag.hash(state);
+            }
+            #[cfg(feature = "glob")]
             RadixRule::Glob { frag, .. } => {
                 "Glob".hash(state);
                 frag.hash(state);
             }
+            #[cfg(feature = "regex")]
             RadixRule::Regex { frag, .. } => {
                 "Regex".hash(state);
                 frag.hash(state);
             }
+            RadixRule::Custom { frag, .. } => {
+                "Custom".hash(state);
+                frag.hash(state);
+            }
         }
     }
 }
@@ -591,6 +1990,7 @@ impl Eq for RadixRule {}
 /// ```
 /// use radixmap::{rule::RadixRule, RadixResult};
 ///
+/// # #[cfg(all(feature = "glob", feature = "regex"))]
 /// fn main() -> RadixResult<()> {
 ///     assert_eq!(RadixRule::from_plain("/api")?, RadixRule::from_plain("/api")?);
 ///     assert_eq!(RadixRule::from_param(":id")?, RadixRule::from_param(":id")?);
@@ -607,15 +2007,21 @@ impl Eq for RadixRule {}
 ///
 ///     Ok(())
 /// }
+/// # #[cfg(not(all(feature = "glob", feature = "regex")))]
+/// # fn main() {}
 /// ```
 impl PartialEq for RadixRule {
     #[inline]
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
-            (RadixRule::Plain { frag: a }, RadixRule::Plain { frag: b }) => a == b,
+            (RadixRule::Plain { frag: a, .. }, RadixRule::Plain { frag: b, .. }) => a == b,
             (RadixRule::Param { frag: a, .. }, RadixRule::Param { frag: b, .. }) => a == b,
+            (RadixRule::Typed { frag: a, .. }, RadixRule::Typed { frag: b, .. }) => a == b,
+            #[cfg(feature = "glob")]
             (RadixRule::Glob { frag: a, .. }, RadixRule::Glob { frag: b, .. }) => a == b,
+            #[cfg(feature = "regex")]
             (RadixRule::Regex { frag: a, .. }, RadixRule::Regex { frag: b, .. }) => a == b,
+            (RadixRule::Custom { frag: a, .. }, RadixRule::Custom { frag: b, .. }) => a == b,
             _ => false
         }
     }
@@ -628,6 +2034,7 @@ impl PartialEq for RadixRule {
 /// ```
 /// use radixmap::{rule::RadixRule, RadixResult};
 ///
+/// # #[cfg(all(feature = "glob", feature = "regex"))]
 /// fn main() -> RadixResult<()> {
 ///     assert_eq!(RadixRule::from_plain("/api")?, "/api");
 ///     assert_eq!(RadixRule::from_param(":id")?, ":id");
@@ -641,6 +2048,8 @@ impl PartialEq for RadixRule {
 ///
 ///     Ok(())
 /// }
+/// # #[cfg(not(all(feature = "glob", feature = "regex")))]
+/// # fn main() {}
 /// ```
 impl PartialEq<&[u8]> for RadixRule {
     #[inline]
@@ -663,4 +2072,51 @@ impl PartialEq<&str> for RadixRule {
     fn eq(&self, other: &&str) -> bool {
         self.origin() == other.as_bytes()
     }
-}
\ No newline at end of file
+}
+
+/// Order by specificity: plain, regex, custom, typed param, param, glob, then by fragment
+///
+/// This is the order in which lookup tries sibling nodes
+///
+/// # Examples
+///
+/// ```
+/// use radixmap::{rule::RadixRule, RadixResult};
+///
+/// # #[cfg(all(feature = "glob", feature = "regex"))]
+/// fn main() -> RadixResult<()> {
+///     let mut rules = vec![
+///         RadixRule::from_glob("*")?,
+///         RadixRule::from_param(":id")?,
+///         RadixRule::from_regex(r"{id:\d+}")?,
+///         RadixRule::from_plain("/api")?,
+///         RadixRule::from_param(":name")?,
+///     ];
+///
+///     rules.sort();
+///
+///     assert_eq!(rules[0], "/api");
+///     assert_eq!(rules[1], r"{id:\d+}");
+///     assert_eq!(rules[2], ":id");
+///     assert_eq!(rules[3], ":name");
+///     assert_eq!(rules[4], "*");
+///
+///     Ok(())
+/// }
+/// # #[cfg(not(all(feature = "glob", feature = "regex")))]
+/// # fn main() {}
+/// ```
+impl Ord for RadixRule {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.rank().cmp(&other.rank()).then_with(|| self.origin().cmp(other.origin()))
+    }
+}
+
+/// < & >
+impl PartialOrd for RadixRule {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
@@ -1,6 +1,8 @@
 //! Radix set implementation
 use super::defs::*;
 use super::map::{self, RadixMap};
+use super::params::{Params, ParamsBuf};
+use super::rule::{RadixConfig, RadixMatcher};
 
 /// Radix set build on top of map
 pub struct RadixSet {
@@ -15,6 +17,138 @@ impl RadixSet {
         Default::default()
     }
 
+    /// Create an empty set whose segments are divided by a custom separator
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{RadixSet, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut set = RadixSet::with_separator(".")?;
+    ///     set.insert("db.:name.host")?;
+    ///
+    ///     assert_eq!(set.contains(b"db.main.host"), true);
+    ///     assert_eq!(set.contains(b"db.main.port"), false);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn with_separator(sep: impl AsRef<[u8]>) -> RadixResult<Self> {
+        Ok(Self { base: RadixMap::with_separator(sep)? })
+    }
+
+    /// Create an empty set with the options of the rules
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{rule::RadixConfig, RadixSet, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut set = RadixSet::with_config(RadixConfig { ignore_case: true, ..Default::default() });
+    ///     set.insert("/Products/List")?;
+    ///
+    ///     assert_eq!(set.contains(b"/products/list"), true);
+    ///     assert_eq!(set.contains(b"/products/item"), false);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn with_config(config: RadixConfig) -> Self {
+        Self { base: RadixMap::with_config(config) }
+    }
+
+    /// The seed which deserializes a set with the options of the rules, which aren't serialized
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use serde::de::DeserializeSeed;
+    /// use radixmap::{rule::RadixConfig, RadixSet, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut config = RadixConfig::with_separator("::")?;
+    ///     config.register("digits", |path: &[u8]| -> Option<(usize, Option<Bytes>)> {
+    ///         let len = path.iter().take_while(|c| c.is_ascii_digit()).count();
+    ///         (len > 0).then_some((len, None))
+    ///     });
+    ///
+    ///     let mut de = serde_json::Deserializer::from_str(r#"["crate::{id:@digits}"]"#);
+    ///     let set = RadixSet::seed(config).deserialize(&mut de).unwrap_or_default();
+    ///
+    ///     assert!(set.contains(b"crate::12345"));
+    ///     assert!(!set.contains(b"crate::abc"));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "serde")]
+    #[inline]
+    pub fn seed(config: RadixConfig) -> Seed {
+        Seed { config }
+    }
+
+    /// Register a custom matcher, the paths inserted afterwards refer to it as {name:@matcher}
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use radixmap::{RadixSet, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut set = RadixSet::new();
+    ///     set.register("even", |path: &[u8]| -> Option<(usize, Option<Bytes>)> {
+    ///         let len = path.iter().take_while(|c| c.is_ascii_digit()).count();
+    ///         match len > 0 && (path[len - 1] - b'0') % 2 == 0 {
+    ///             true => Some((len, None)),
+    ///             false => None,
+    ///         }
+    ///     });
+    ///
+    ///     set.insert("/page/{n:@even}")?;
+    ///
+    ///     assert_eq!(set.contains(b"/page/12"), true);
+    ///     assert_eq!(set.contains(b"/page/13"), false);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn register(&mut self, name: impl Into<Bytes>, matcher: impl RadixMatcher + 'static) {
+        self.base.register(name, matcher);
+    }
+
+    /// The conflicts recorded instead of being rejected, see RadixConfig::lenient
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{rule::RadixConfig, RadixSet, RadixResult};
+    ///
+    /// # #[cfg(feature = "glob")]
+    /// fn main() -> RadixResult<()> {
+    ///     let mut set = RadixSet::with_config(RadixConfig { lenient: true, ..Default::default() });
+    ///     set.insert("/files/*")?;
+    ///     set.insert("/files/{*rest}")?;
+    ///
+    ///     assert_eq!(set.len(), 2);
+    ///     assert_eq!(set.warnings().len(), 1);
+    ///
+    ///     Ok(())
+    /// }
+    /// # #[cfg(not(feature = "glob"))]
+    /// # fn main() {}
+    /// ```
+    #[inline]
+    pub fn warnings(&self) -> &[RadixError] {
+        self.base.warnings()
+    }
+
     /// The size of the set
     ///
     /// # Examples
@@ -68,8 +202,9 @@ impl RadixSet {
     ///
     /// ```
     /// use bytes::Bytes;
-    /// use radixmap::{RadixSet, RadixResult};
+    /// use radixmap::{params::Params, RadixSet, RadixResult};
     ///
+    /// # #[cfg(all(feature = "glob", feature = "regex"))]
     /// fn main() -> RadixResult<()> {
     ///     let mut set = RadixSet::new();
     ///     set.insert("/api/v1/user/12345")?;
@@ -78,22 +213,100 @@ impl RadixSet {
     ///     set.insert("/api/v4/user/{id:[^0-9]+}")?;
     ///     set.insert("/api/v5/user/*345")?;
     ///
-    ///     assert_eq!(set.capture(b"/api/v1/user/12345"), (true, vec![]));
-    ///     assert_eq!(set.capture(b"/api/v2/user/12345"), (true, vec![(Bytes::from("id"), "12345".as_bytes())]));
-    ///     assert_eq!(set.capture(b"/api/v3/user/12345"), (true, vec![(Bytes::from("id"), "12345".as_bytes())]));
-    ///     assert_eq!(set.capture(b"/api/v4/user/12345"), (false, vec![]));
-    ///     assert_eq!(set.capture(b"/api/v5/user/12345"), (true, vec![(Bytes::from("*"), "12345".as_bytes())]));
-    ///     assert_eq!(set.capture(b"/api/v6"), (false, vec![]));
+    ///     assert_eq!(set.capture(b"/api/v1/user/12345"), (true, Params::new()));
+    ///     assert_eq!(set.capture(b"/api/v2/user/12345"), (true, Params::from(vec![(Bytes::from("id"), "12345".as_bytes())])));
+    ///     assert_eq!(set.capture(b"/api/v3/user/12345"), (true, Params::from(vec![(Bytes::from("id"), "12345".as_bytes())])));
+    ///     assert_eq!(set.capture(b"/api/v4/user/12345"), (false, Params::new()));
+    ///     assert_eq!(set.capture(b"/api/v5/user/12345"), (true, Params::from(vec![(Bytes::from("*"), "12345".as_bytes())])));
+    ///     assert_eq!(set.capture(b"/api/v6"), (false, Params::new()));
     ///
     ///     Ok(())
     /// }
+    /// # #[cfg(not(all(feature = "glob", feature = "regex")))]
+    /// # fn main() {}
     /// ```
     #[inline]
-    pub fn capture<'u>(&self, path: &'u [u8]) -> (bool, Vec<(Bytes, &'u [u8])>) {
+    pub fn capture<'u>(&self, path: &'u [u8]) -> (bool, Params<'u>) {
         let (data, capt) = self.base.capture(path);
         (data.is_some(), capt)
     }
 
+    /// Same as capture, but collect the captures into a caller's buffer, see `RadixMap::capture_into`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{params::ParamsBuf, RadixSet, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let set = RadixSet::try_from(["/user/:id"])?;
+    ///     let mut buff = ParamsBuf::new();
+    ///
+    ///     let (data, capt) = set.capture_into(b"/user/12345", &mut buff);
+    ///     assert!(data);
+    ///     assert_eq!(capt.get("id"), Some(b"12345".as_ref()));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn capture_into<'u>(&self, path: &'u [u8], buffer: &'u mut ParamsBuf) -> (bool, Params<'u>) {
+        let (data, capt) = self.base.capture_into(path, buffer);
+        (data.is_some(), capt)
+    }
+
+    /// Same as capture, but the captures are owned slices of the path, see `RadixMap::capture_bytes`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use radixmap::{RadixSet, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let set = RadixSet::try_from(["/user/:id"])?;
+    ///     let (_, capt) = set.capture_bytes(Bytes::from("/user/12345"));
+    ///
+    ///     assert_eq!(capt.get("id"), Some(b"12345".as_ref()));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn capture_bytes(&self, path: Bytes) -> (bool, Params<'static>) {
+        let (data, capt) = self.base.capture_bytes(path);
+        (data.is_some(), capt)
+    }
+
+    /// Iterate all patterns matching the path along with their captures
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use radixmap::{params::Params, RadixSet, RadixResult};
+    ///
+    /// # #[cfg(feature = "glob")]
+    /// fn main() -> RadixResult<()> {
+    ///     let set = RadixSet::try_from(["/admin/*", "/admin/:page", "/admin/users", "/public/*"])?;
+    ///
+    ///     let mut iter = set.matches(b"/admin/users");
+    ///
+    ///     assert_eq!(iter.next(), Some((&Bytes::from("/admin/users"), Params::new())));
+    ///     assert_eq!(iter.next(), Some((&Bytes::from("/admin/:page"), Params::from(vec![(Bytes::from("page"), "users".as_bytes())]))));
+    ///     assert_eq!(iter.next(), Some((&Bytes::from("/admin/*"), Params::from(vec![(Bytes::from("*"), "users".as_bytes())]))));
+    ///     assert_eq!(iter.next(), None);
+    ///
+    ///     Ok(())
+    /// }
+    /// # #[cfg(not(feature = "glob"))]
+    /// # fn main() {}
+    /// ```
+    #[inline]
+    pub fn matches<'u>(&self, path: &'u [u8]) -> Matches<'_, 'u> {
+        Matches::from((self, path))
+    }
+
     /// Check if the tree contains specific path
     ///
     /// # Examples
@@ -176,7 +389,86 @@ impl RadixSet {
         self.base.insert(path, ()).map(|data| data.is_some())
     }
 
-    /// Remove the nodes along the path, affecting data nodes only
+    /// Insert with an explicit priority, see `RadixMap::insert_with_priority`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{RadixSet, RadixResult};
+    ///
+    /// # #[cfg(feature = "regex")]
+    /// fn main() -> RadixResult<()> {
+    ///     let mut set = RadixSet::new();
+    ///
+    ///     assert_eq!(set.insert(r"/{id:\d+}")?, false);
+    ///     assert_eq!(set.insert_with_priority("/:id", 1)?, false);
+    ///
+    ///     assert_eq!(set.capture(b"/123").1.get("id"), Some(b"123".as_ref()));
+    ///
+    ///     // a catch-all tried before its siblings would shadow them
+    ///     assert!(set.insert_with_priority("/*", 2).is_err());
+    ///
+    ///     Ok(())
+    /// }
+    /// # #[cfg(not(feature = "regex"))]
+    /// # fn main() {}
+    /// ```
+    #[inline]
+    pub fn insert_with_priority(&mut self, path: impl Into<Bytes>, priority: i32) -> RadixResult<bool> {
+        self.base.insert_with_priority(path, (), priority).map(|data| data.is_some())
+    }
+
+    /// Get the entry of the raw path for in-place manipulation
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{set::Entry, RadixSet, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut set = RadixSet::new();
+    ///     set.insert("/api/v1")?;
+    ///
+    ///     assert!(matches!(set.entry("/api/v1")?, Entry::Occupied(_)));
+    ///     assert!(matches!(set.entry("/api/v2")?, Entry::Vacant(_)));
+    ///
+    ///     set.entry("/api/v2")?.or_insert(());
+    ///
+    ///     assert_eq!(set.len(), 2);
+    ///     assert_eq!(set.contains(b"/api/v2"), true);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn entry(&mut self, path: impl Into<Bytes>) -> RadixResult<Entry<'_>> {
+        self.base.entry(path)
+    }
+
+    /// Insert the path if not exist and return the stored one
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use radixmap::{RadixSet, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut set = RadixSet::new();
+    ///
+    ///     assert_eq!(set.get_or_insert("/api/:id")?, &Bytes::from("/api/:id"));
+    ///     assert_eq!(set.get_or_insert("/api/:id")?, &Bytes::from("/api/:id"));
+    ///     assert_eq!(set.len(), 1);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn get_or_insert(&mut self, path: impl Into<Bytes>) -> RadixResult<&Bytes> {
+        Ok(self.base.entry(path)?.or_insert_item(()).0)
+    }
+
+    /// Remove the raw pattern from the set
     ///
     /// # Examples
     ///
@@ -188,11 +480,14 @@ impl RadixSet {
     ///     set.insert("/api/v1")?;
     ///     set.insert("/api/v2")?;
     ///     set.insert("/api")?;
-    ///
-    ///     assert_eq!(set.len(), 3);
-    ///     assert_eq!(set.remove(b"/"), false);      // non-data node
-    ///     assert_eq!(set.remove(b"/api"), true);    // len - 1
-    ///     assert_eq!(set.remove(b"/api/v2"), true); // len - 1
+    ///     set.insert("/user/:id")?;
+    ///
+    ///     assert_eq!(set.len(), 4);
+    ///     assert_eq!(set.remove(b"/"), false);         // non-data node
+    ///     assert_eq!(set.remove(b"/api"), true);       // len - 1
+    ///     assert_eq!(set.remove(b"/api/v2"), true);    // len - 1
+    ///     assert_eq!(set.remove(b"/user/123"), false); // pattern only
+    ///     assert_eq!(set.remove(b"/user/:id"), true);  // len - 1
     ///     assert_eq!(set.len(), 1);
     ///
     ///     Ok(())
@@ -203,6 +498,70 @@ impl RadixSet {
         self.base.remove(path).is_some()
     }
 
+    /// Remove the pattern which the concrete path resolves to and return it
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use radixmap::{RadixSet, RadixResult};
+    ///
+    /// fn main() -> RadixResult<()> {
+    ///     let mut set = RadixSet::new();
+    ///     set.insert("/user/:id")?;
+    ///
+    ///     assert_eq!(set.remove_matching(b"/user/123"), Some(Bytes::from("/user/:id")));
+    ///     assert_eq!(set.remove_matching(b"/user/123"), None);
+    ///     assert_eq!(set.len(), 0);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn remove_matching(&mut self, path: &[u8]) -> Option<Bytes> {
+        self.base.remove_matching(path).map(|item| item.0)
+    }
+
+    /// Remove every pattern matching the concrete path and return them
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use radixmap::{RadixSet, RadixResult};
+    ///
+    /// # #[cfg(feature = "glob")]
+    /// fn main() -> RadixResult<()> {
+    ///     let mut set = RadixSet::new();
+    ///     set.insert("/user/:id")?;
+    ///     set.insert("/user/*")?;
+    ///     set.insert("/user/new")?;
+    ///
+    ///     assert_eq!(set.remove_all_matching(b"/user/123"), vec![Bytes::from("/user/:id"), Bytes::from("/user/*")]);
+    ///     assert_eq!(set.len(), 1);
+    ///
+    ///     Ok(())
+    /// }
+    /// # #[cfg(not(feature = "glob"))]
+    /// # fn main() {}
+    /// ```
+    #[inline]
+    pub fn remove_all_matching(&mut self, path: &[u8]) -> Vec<Bytes> {
+        self.base.remove_all_matching(path).into_iter().map(|item| item.0).collect()
+    }
+
+    /// Remove the empty nodes left by entries and merge the divided plain fragments
+    #[inline]
+    pub fn compact(&mut self) {
+        self.base.compact();
+    }
+
+    /// Compact the tree and release the unused capacity
+    #[inline]
+    pub fn shrink_to_fit(&mut self) {
+        self.base.shrink_to_fit();
+    }
+
     /// Clear the radix set but preserve its capacity
     ///
     /// # Examples
@@ -229,6 +588,50 @@ impl RadixSet {
     pub fn clear(&mut self) {
         self.base.clear();
     }
+
+    /// Persist the whole tree, same as the map
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radixmap::{RadixSet, RadixResult};
+    ///
+    /// # #[cfg(feature = "glob")]
+    /// fn main() -> RadixResult<()> {
+    ///     let set = RadixSet::try_from(["/api/v1", "/api/:id", "/static/*"])?;
+    ///
+    ///     let mut snapshot = vec![];
+    ///     set.write_snapshot(&mut snapshot)?;
+    ///
+    ///     let load = RadixSet::read_snapshot(snapshot.as_slice())?;
+    ///
+    ///     assert_eq!(load, set);
+    ///     assert_eq!(load.contains(b"/api/12345"), true);
+    ///
+    ///     Ok(())
+    /// }
+    /// # #[cfg(not(feature = "glob"))]
+    /// # fn main() {}
+    /// ```
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn write_snapshot(&self, writer: &mut impl std::io::Write) -> RadixResult<()> {
+        self.base.write_snapshot(writer)
+    }
+
+    /// Load the tree persisted by write_snapshot
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn read_snapshot(reader: impl std::io::Read) -> RadixResult<Self> {
+        RadixMap::read_snapshot(reader).map(|base| Self { base })
+    }
+
+    /// Same as read_snapshot, the custom matchers are taken from the config
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn read_snapshot_with_config(reader: impl std::io::Read, config: RadixConfig) -> RadixResult<Self> {
+        RadixMap::read_snapshot_with_config(reader, config).map(|base| Self { base })
+    }
 }
 
 // -----------------------------------------------------------------------------
@@ -334,7 +737,7 @@ impl Clone for RadixSet {
 /// ```
 impl Debug for RadixSet {
     #[inline]
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         f.debug_set().entries(self.iter()).finish()
     }
 }
@@ -349,6 +752,89 @@ impl PartialEq for RadixSet {
     }
 }
 
+/// Serialize as a sequence of the raw patterns, in the iterating order. The config isn't
+/// serialized, restore it via RadixSet::seed
+///
+/// # Examples
+///
+/// ```
+/// use radixmap::{RadixSet, RadixResult};
+///
+/// fn main() -> RadixResult<()> {
+///     let set = RadixSet::try_from(["/api/v1", "/api/:id", "/api"])?;
+///
+///     assert_eq!(serde_json::to_string(&set).ok(), Some(r#"["/api","/api/v1","/api/:id"]"#.to_string()));
+///
+///     Ok(())
+/// }
+/// ```
+#[cfg(feature = "serde")]
+impl serde::Serialize for RadixSet {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter().map(|path| map::Pattern(path.clone())))
+    }
+}
+
+/// Deserialize from a sequence of the raw patterns, the tree is rebuilt with the default config,
+/// see RadixSet::seed for another one, and the malformed pattern is reported along with its key
+///
+/// # Examples
+///
+/// ```
+/// use radixmap::RadixSet;
+///
+/// let set: RadixSet = serde_json::from_str(r#"["/api","/api/v1","/api/:id"]"#).unwrap();
+///
+/// assert_eq!(set.len(), 3);
+/// assert_eq!(set.contains(b"/api/12345"), true);
+///
+/// let err = serde_json::from_str::<RadixSet>(r#"["/api","/api/{id"]"#).unwrap_err();
+/// assert!(err.to_string().starts_with("invalid pattern '/api/{id': missing closing sign '}'"));
+/// ```
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for RadixSet {
+    #[inline]
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        serde::de::DeserializeSeed::deserialize(Self::seed(RadixConfig::default()), deserializer)
+    }
+}
+
+/// The seed which deserializes a set with the given config, see RadixSet::seed
+#[cfg(feature = "serde")]
+pub struct Seed {
+    /// The options of the rules
+    config: RadixConfig,
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::de::DeserializeSeed<'de> for Seed {
+    type Value = RadixSet;
+
+    fn deserialize<D: serde::Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        struct Visitor(RadixConfig);
+
+        impl<'de> serde::de::Visitor<'de> for Visitor {
+            type Value = RadixSet;
+
+            fn expecting(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+                f.write_str("a sequence of patterns")
+            }
+
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+                let mut set = RadixSet::with_config(self.0);
+
+                while let Some(map::Pattern(path)) = access.next_element()? {
+                    set.insert(path.clone()).map_err(|err| map::Pattern::error(&path, err))?;
+                }
+
+                Ok(set)
+            }
+        }
+
+        deserializer.deserialize_seq(Visitor(self.config))
+    }
+}
+
 // -----------------------------------------------------------------------------
 
 /// Re-import Order
@@ -357,4 +843,33 @@ pub type Order = map::Order;
 // -----------------------------------------------------------------------------
 
 /// Re-import Iterator
-pub type Iter<'n> = map::Keys<'n, ()>;
\ No newline at end of file
+pub type Iter<'n> = map::Keys<'n, ()>;
+
+// -----------------------------------------------------------------------------
+
+/// Re-import Entry
+pub type Entry<'n> = map::Entry<'n, ()>;
+
+// -----------------------------------------------------------------------------
+
+/// Iterator for all patterns matching a path
+#[derive(Clone)]
+pub struct Matches<'n, 'u> {
+    iter: map::Matches<'n, 'u, ()>
+}
+
+impl<'n, 'u> From<(&'n RadixSet, &'u [u8])> for Matches<'n, 'u> {
+    #[inline]
+    fn from((value, path): (&'n RadixSet, &'u [u8])) -> Self {
+        Self { iter: value.base.matches(path) }
+    }
+}
+
+impl<'n, 'u> Iterator for Matches<'n, 'u> {
+    type Item = (&'n Bytes, Params<'u>);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(path, _, capt)| (path, capt))
+    }
+}
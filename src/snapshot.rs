@@ -0,0 +1,404 @@
+//! Snapshot persists the whole tree in a versioned binary format
+//!
+//! The layout is a header of the magic `RDXS`, the version and the config, followed by the nodes
+//! in pre-order and a FNV-1a checksum of all the bytes before it. Every node keeps its fragment,
+//! priority, path, alias, data and the number of its children, so loading restores the tree
+//! without splitting the paths again, only the glob and regex rules are compiled once more
+#![cfg_attr(not(feature = "std"), allow(dead_code))]
+use super::defs::*;
+use super::node::{RadixAlias, RadixNode};
+use super::rule::{RadixConfig, RadixRule};
+
+/// The leading bytes of a snapshot
+const MAGIC: &[u8; 4] = b"RDXS";
+
+/// The format version, bumped on every incompatible change
+const VERSION: u32 = 1;
+
+/// The key of the special child, regular children are keyed by their first byte
+const SPECIAL: u32 = u32::MAX;
+
+/// Encode the data of a node into bytes and decode it back, implement it to persist custom values
+///
+/// # Examples
+///
+/// ```
+/// use radixmap::{snapshot::RadixCodec, RadixError, RadixResult};
+///
+/// #[derive(Debug)]
+/// struct Handler(u16);
+///
+/// impl RadixCodec for Handler {
+///     fn encode(&self, buf: &mut Vec<u8>) {
+///         self.0.encode(buf);
+///     }
+///
+///     fn decode(data: &[u8]) -> RadixResult<Self> {
+///         u16::decode(data).map(Handler)
+///     }
+/// }
+///
+/// let mut buf = vec![];
+/// Handler(404).encode(&mut buf);
+///
+/// assert!(matches!(Handler::decode(&buf), Ok(Handler(404))));
+/// assert!(matches!(Handler::decode(&buf[..1]), Err(RadixError::SnapshotInvalid(_))));
+/// ```
+pub trait RadixCodec: Sized {
+    /// Append the encoded value to the buffer
+    fn encode(&self, buf: &mut Vec<u8>);
+
+    /// Decode the value from the exact bytes which encode produced
+    fn decode(data: &[u8]) -> RadixResult<Self>;
+}
+
+macro_rules! codec {
+    ($($kind:ty),+) => {$(
+        impl RadixCodec for $kind {
+            #[inline]
+            fn encode(&self, buf: &mut Vec<u8>) {
+                buf.extend_from_slice(&self.to_le_bytes());
+            }
+
+            #[inline]
+            fn decode(data: &[u8]) -> RadixResult<Self> {
+                match data.try_into() {
+                    Ok(data) => Ok(<$kind>::from_le_bytes(data)),
+                    Err(_) => Err(RadixError::SnapshotInvalid("value length mismatch")),
+                }
+            }
+        }
+    )+};
+}
+
+codec!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+impl RadixCodec for () {
+    #[inline]
+    fn encode(&self, _: &mut Vec<u8>) {}
+
+    #[inline]
+    fn decode(data: &[u8]) -> RadixResult<Self> {
+        match data.is_empty() {
+            true => Ok(()),
+            false => Err(RadixError::SnapshotInvalid("value length mismatch")),
+        }
+    }
+}
+
+impl RadixCodec for bool {
+    #[inline]
+    fn encode(&self, buf: &mut Vec<u8>) {
+        buf.push(*self as u8);
+    }
+
+    #[inline]
+    fn decode(data: &[u8]) -> RadixResult<Self> {
+        match data {
+            [0] => Ok(false),
+            [1] => Ok(true),
+            _ => Err(RadixError::SnapshotInvalid("value is not a bool")),
+        }
+    }
+}
+
+impl RadixCodec for Bytes {
+    #[inline]
+    fn encode(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(self);
+    }
+
+    #[inline]
+    fn decode(data: &[u8]) -> RadixResult<Self> {
+        Ok(Bytes::copy_from_slice(data))
+    }
+}
+
+impl RadixCodec for Vec<u8> {
+    #[inline]
+    fn encode(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(self);
+    }
+
+    #[inline]
+    fn decode(data: &[u8]) -> RadixResult<Self> {
+        Ok(data.to_vec())
+    }
+}
+
+impl RadixCodec for String {
+    #[inline]
+    fn encode(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(self.as_bytes());
+    }
+
+    #[inline]
+    fn decode(data: &[u8]) -> RadixResult<Self> {
+        Ok(core::str::from_utf8(data)?.into())
+    }
+}
+
+// -----------------------------------------------------------------------------
+
+/// Internal use only, serialize the tree along with its config
+pub(crate) fn encode<V: RadixCodec>(root: &RadixNode<V>, size: usize, config: &RadixConfig) -> Vec<u8> {
+    let mut buf = Vec::new();
+
+    buf.extend_from_slice(MAGIC);
+    buf.extend_from_slice(&VERSION.to_le_bytes());
+    bytes(&mut buf, &config.separator);
+    buf.push((config.ignore_case as u8) | ((config.lenient as u8) << 1));
+    buf.extend_from_slice(&(size as u64).to_le_bytes());
+
+    node(&mut buf, root);
+
+    let sum = checksum(&buf);
+    buf.extend_from_slice(&sum.to_le_bytes());
+    buf
+}
+
+/// Internal use only, rebuild the tree and restore the config, the matchers are taken from
+/// the config passed in
+pub(crate) fn decode<V: RadixCodec>(data: &[u8], mut config: RadixConfig) -> RadixResult<(RadixNode<V>, usize, RadixConfig)> {
+    if data.len() < MAGIC.len() + 4 + 8 || !data.starts_with(MAGIC) {
+        return Err(RadixError::SnapshotInvalid("not a snapshot"));
+    }
+
+    let (data, sum) = data.split_at(data.len() - 8);
+    if sum != checksum(data).to_le_bytes() {
+        return Err(RadixError::SnapshotInvalid("checksum mismatch"));
+    }
+
+    let mut cursor = Cursor { data: &data[MAGIC.len()..] };
+    if cursor.u32()? != VERSION {
+        return Err(RadixError::SnapshotInvalid("unsupported version"));
+    }
+
+    config.separator = cursor.bytes()?;
+    if config.separator.is_empty() {
+        return Err(RadixError::SnapshotInvalid("separator is empty"));
+    }
+
+    let flags = cursor.u8()?;
+    config.ignore_case = flags & 1 != 0;
+    config.lenient = flags & 2 != 0;
+
+    let size = cursor.u64()?;
+
+    // the children follow their parent, a node is attached once all of its children are read
+    let (root, count) = cursor.node(&config)?;
+    if !root.rule.is_plain() || !root.rule.origin().is_empty() {
+        return Err(RadixError::SnapshotInvalid("root is not empty"));
+    }
+
+    let mut stack: Vec<(RadixNode<V>, u32, u32)> = vec![(root, count, SPECIAL)];
+    let mut total = 0;
+
+    loop {
+        let Some((_, count, _)) = stack.last_mut() else {
+            return Err(RadixError::SnapshotInvalid("unbalanced nodes"));
+        };
+
+        if *count > 0 {
+            *count -= 1;
+
+            let key = cursor.u32()?;
+            let (node, count) = cursor.node(&config)?;
+
+            // the regular node must be reachable by its first byte
+            let first = node.rule.origin().first().map(|&byte| match node.rule.is_icase() {
+                true => byte.to_ascii_lowercase() as u32,
+                false => byte as u32,
+            });
+
+            if key != SPECIAL && first != Some(key) {
+                return Err(RadixError::SnapshotInvalid("regular key mismatch"));
+            }
+
+            stack.push((node, count, key));
+            continue;
+        }
+
+        let Some((node, _, key)) = stack.pop() else {
+            return Err(RadixError::SnapshotInvalid("unbalanced nodes"));
+        };
+
+        if !node.is_empty() {
+            total += 1;
+        }
+
+        let parent = match stack.last_mut() {
+            Some((parent, _, _)) => parent,
+            None if cursor.data.is_empty() && total == size => return Ok((node, total as usize, config)),
+            None if cursor.data.is_empty() => return Err(RadixError::SnapshotInvalid("size mismatch")),
+            None => return Err(RadixError::SnapshotInvalid("trailing bytes")),
+        };
+
+        let exist = match key {
+            SPECIAL if node.rule.is_special() => parent.next.special.insert(node.rule.origin().clone(), node).is_some(),
+            SPECIAL => return Err(RadixError::SnapshotInvalid("plain rule keyed as special")),
+            _ if node.rule.is_special() => return Err(RadixError::SnapshotInvalid("special rule keyed as regular")),
+            _ => parent.next.regular.insert(key as usize, node).is_some(),
+        };
+
+        if exist {
+            return Err(RadixError::SnapshotInvalid("duplicate child"));
+        }
+    }
+}
+
+/// Internal use only, append a node and its descendants in pre-order
+fn node<V: RadixCodec>(buf: &mut Vec<u8>, node: &RadixNode<V>) {
+    buf.push(match (node.rule.is_plain(), node.rule.is_icase()) {
+        (true, false) => 0,
+        (true, true) => 1,
+        (false, _) => 2,
+    });
+
+    bytes(buf, node.rule.origin());
+    buf.extend_from_slice(&node.priority.to_le_bytes());
+    bytes(buf, &node.path);
+
+    match &node.alias {
+        Some(alias) => {
+            buf.push(1);
+            bytes(buf, &alias.link);
+            buf.extend_from_slice(&(alias.defaults.len() as u32).to_le_bytes());
+
+            for (name, value) in &alias.defaults {
+                bytes(buf, name);
+                bytes(buf, value);
+            }
+        }
+        None => buf.push(0),
+    }
+
+    match &node.data {
+        Some(data) => {
+            let mut value = Vec::new();
+            data.encode(&mut value);
+
+            buf.push(1);
+            bytes(buf, &value);
+        }
+        None => buf.push(0),
+    }
+
+    buf.extend_from_slice(&((node.next.regular.len() + node.next.special.len()) as u32).to_le_bytes());
+
+    for (key, next) in node.next.regular.iter() {
+        buf.extend_from_slice(&(key as u32).to_le_bytes());
+        self::node(buf, next);
+    }
+
+    for next in node.next.special.values() {
+        buf.extend_from_slice(&SPECIAL.to_le_bytes());
+        self::node(buf, next);
+    }
+}
+
+/// Internal use only, append the length-prefixed bytes
+#[inline]
+pub(crate) fn bytes(buf: &mut Vec<u8>, data: &[u8]) {
+    buf.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    buf.extend_from_slice(data);
+}
+
+/// Internal use only, FNV-1a of the bytes
+#[inline]
+pub(crate) fn checksum(data: &[u8]) -> u64 {
+    let mut hasher = FnvHasher::default();
+    hasher.write(data);
+    hasher.finish()
+}
+
+/// Internal use only, read the snapshot from the front, running out of bytes is an error
+pub(crate) struct Cursor<'a> {
+    pub(crate) data: &'a [u8],
+}
+
+impl<'a> Cursor<'a> {
+    #[inline]
+    pub(crate) fn take(&mut self, len: usize) -> RadixResult<&'a [u8]> {
+        if self.data.len() < len {
+            return Err(RadixError::SnapshotInvalid("unexpected end of data"));
+        }
+
+        let (head, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(head)
+    }
+
+    #[inline]
+    pub(crate) fn u8(&mut self) -> RadixResult<u8> {
+        self.take(1).map(|data| data[0])
+    }
+
+    #[inline]
+    pub(crate) fn u32(&mut self) -> RadixResult<u32> {
+        u32::decode(self.take(4)?)
+    }
+
+    #[inline]
+    pub(crate) fn u64(&mut self) -> RadixResult<u64> {
+        u64::decode(self.take(8)?)
+    }
+
+    #[inline]
+    pub(crate) fn i32(&mut self) -> RadixResult<i32> {
+        i32::decode(self.take(4)?)
+    }
+
+    #[inline]
+    pub(crate) fn slice(&mut self) -> RadixResult<&'a [u8]> {
+        let len = self.u32()? as usize;
+        self.take(len)
+    }
+
+    #[inline]
+    pub(crate) fn bytes(&mut self) -> RadixResult<Bytes> {
+        self.slice().map(Bytes::copy_from_slice)
+    }
+
+    #[inline]
+    pub(crate) fn flag(&mut self) -> RadixResult<bool> {
+        match self.u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(RadixError::SnapshotInvalid("unknown flag")),
+        }
+    }
+
+    /// Read a node without its children, and the number of the children following it
+    fn node<V: RadixCodec>(&mut self, config: &RadixConfig) -> RadixResult<(RadixNode<V>, u32)> {
+        let (plain, icase) = match self.u8()? {
+            0 => (true, false),
+            1 => (true, true),
+            2 => (false, false),
+            _ => return Err(RadixError::SnapshotInvalid("unknown rule")),
+        };
+
+        let rule = RadixRule::restore(self.bytes()?, plain, icase, config)?;
+        let mut node = RadixNode::from(rule);
+
+        node.priority = self.i32()?;
+        node.path = self.bytes()?;
+
+        if self.flag()? {
+            let link = self.bytes()?;
+            let mut defaults = vec![];
+
+            for _ in 0..self.u32()? {
+                defaults.push((self.bytes()?, self.bytes()?));
+            }
+
+            node.alias = Some(Box::new(RadixAlias { link, defaults }));
+        }
+
+        if self.flag()? {
+            node.data = Some(V::decode(self.slice()?)?);
+        }
+
+        Ok((node, self.u32()?))
+    }
+}